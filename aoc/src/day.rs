@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+// Helpers for driving the day crates through cargo, shared by the subcommands.
+
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc lives inside the workspace")
+        .to_path_buf()
+}
+
+// Accepts "6", "06" and "day06".
+pub fn parse_day(arg: &str) -> Option<String> {
+    let number = arg.strip_prefix("day").unwrap_or(arg).parse::<u32>().ok()?;
+    (1..=25)
+        .contains(&number)
+        .then(|| format!("day{:02}", number))
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DayRun {
+    // (part, answer) in the order the day printed them.
+    pub answers: Vec<(String, String)>,
+    pub timings: HashMap<String, Duration>,
+    pub total: Duration,
+}
+
+impl DayRun {
+    pub fn answer(&self, part: &str) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|(p, _)| p == part)
+            .map(|(_, answer)| answer.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+// Reads the "dayNN part one: X" lines of stdout and the "dayNN <step> took Nus" lines
+// the runner writes to stderr when `AOC_TIMINGS` is set. Timings of the parts are
+// keyed like the answers ("one"), any other step by its name ("parse").
+pub fn parse_output(day: &str, stdout: &str, stderr: &str) -> DayRun {
+    let prefix = format!("{} ", day);
+    let answers = stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix(&prefix)?
+                .strip_prefix("part ")?
+                .split_once(": ")
+        })
+        .map(|(part, answer)| (part.to_string(), answer.to_string()))
+        .collect();
+    let timings = stderr
+        .lines()
+        .filter_map(|line| {
+            let (step, took) = line.strip_prefix(&prefix)?.split_once(" took ")?;
+            let micros = took.strip_suffix("us")?.parse().ok()?;
+            let step = step.strip_prefix("part ").unwrap_or(step);
+            Some((step.to_string(), Duration::from_micros(micros)))
+        })
+        .collect();
+    DayRun {
+        answers,
+        timings,
+        total: Duration::ZERO,
+    }
+}
+
+fn cargo(root: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(std::env::var("CARGO").unwrap_or("cargo".to_string()));
+    command.current_dir(root).args(args);
+    command
+}
+
+// Release builds, like `run_day`, so that the timings mean something.
+pub fn build(root: &Path, day: &str) -> Result<(), String> {
+    let output = cargo(root, &["build", "-q", "--release", "-p", day])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+pub fn parse_test_summary(stdout: &str) -> TestSummary {
+    let count = |line: &str, what: &str| -> usize {
+        line.split(';')
+            .find_map(|field| {
+                field
+                    .trim()
+                    .strip_suffix(what)?
+                    .trim()
+                    .rsplit(' ')
+                    .next()?
+                    .parse()
+                    .ok()
+            })
+            .unwrap_or(0)
+    };
+    stdout
+        .lines()
+        .filter(|line| line.starts_with("test result:"))
+        .fold(TestSummary::default(), |summary, line| TestSummary {
+            passed: summary.passed + count(line, "passed"),
+            failed: summary.failed + count(line, "failed"),
+        })
+}
+
+// Runs the day's tests, i.e. its examples. Fails only if they could not be built.
+pub fn run_examples(root: &Path, day: &str) -> Result<TestSummary, String> {
+    let output = cargo(root, &["test", "-q", "-p", day])
+        .output()
+        .map_err(|e| e.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() && !stdout.contains("test result:") {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    Ok(parse_test_summary(&stdout))
+}
+
+// Runs the day's release build on its input. `env` is passed on to the day, e.g.
+// `AOC_SVG_DIR` to have it write its visualisation.
+pub fn run_day(
+    root: &Path,
+    day: &str,
+    args: &[&str],
+    env: &[(&str, &Path)],
+) -> Result<DayRun, String> {
+    let start = Instant::now();
+    let output = cargo(root, &["run", "-q", "--release", "-p", day, "--"])
+        .args(args)
+        .env("AOC_TIMINGS", "1")
+        .envs(env.iter().copied())
+        .output()
+        .map_err(|e| e.to_string())?;
+    let total = start.elapsed();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(stderr.into_owned());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(DayRun {
+        total,
+        ..parse_output(day, &stdout, &stderr)
+    })
+}
+
+// Hands the terminal to the day's simulation player. Only days with a `tui` feature
+// have one; cargo reports the others.
+pub fn play(root: &Path, day: &str) -> std::io::Result<()> {
+    let args = ["run", "-q", "--release", "-p", day, "--features", "tui"];
+    let status = cargo(root, &args).args(["--", "--tui"]).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("{} {}", day, status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_names() {
+        assert_eq!(parse_day("6"), Some("day06".to_string()));
+        assert_eq!(parse_day("06"), Some("day06".to_string()));
+        assert_eq!(parse_day("day25"), Some("day25".to_string()));
+        assert_eq!(parse_day("26"), None);
+        assert_eq!(parse_day("six"), None);
+    }
+
+    #[test]
+    fn day_output() {
+        let run = parse_output(
+            "day06",
+            "day06 part one: 41\nday06 part two: 6\n",
+            "day06 parse took 20us\nday06 part one took 1500us\nwarning: something\n",
+        );
+        assert_eq!(run.answer("one"), Some("41"));
+        assert_eq!(run.answer("two"), Some("6"));
+        assert_eq!(run.answer("three"), None);
+        assert_eq!(run.timings["one"], Duration::from_micros(1500));
+        assert_eq!(run.timings["parse"], Duration::from_micros(20));
+        assert!(!run.timings.contains_key("two"));
+    }
+
+    #[test]
+    fn test_summaries() {
+        let stdout = "\nrunning 3 tests\n...\ntest result: ok. 3 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n\
+            test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s\n";
+        assert_eq!(
+            parse_test_summary(stdout),
+            TestSummary {
+                passed: 4,
+                failed: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,46 @@
+mod day;
+mod report;
+mod watch;
+
+use std::path::Path;
+
+const USAGE: &str = "\
+Usage: aoc <COMMAND>
+
+Commands:
+  watch <day>      Rerun a day's examples and input whenever its source, utils or input change
+  report [<path>]  Write answers, timings, example results and visualisations of every day
+                   to a static HTML page (default: target/report.html)
+  tui <day>        Play the day's simulation in the terminal (days 06, 14, 15, 16 and 18)
+";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let result = match args.as_slice() {
+        ["watch", day] => match day::parse_day(day) {
+            Some(day) => watch::watch(&day),
+            None => fail(&format!("unknown day {}", day)),
+        },
+        ["report"] => report::report(&day::workspace_root().join("target").join("report.html")),
+        ["report", path] => report::report(Path::new(path)),
+        ["tui", day] => match day::parse_day(day) {
+            Some(day) => day::play(&day::workspace_root(), &day),
+            None => fail(&format!("unknown day {}", day)),
+        },
+        ["-h" | "--help"] => {
+            print!("{}", USAGE);
+            return;
+        }
+        _ => fail("missing or unknown command"),
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
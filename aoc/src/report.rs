@@ -0,0 +1,211 @@
+use crate::day::{self, DayRun, TestSummary};
+use std::path::Path;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; color: #222; }
+section { border-top: 1px solid #ccc; padding: 1em 0; }
+table { border-collapse: collapse; margin: 0.5em 0; }
+td, th { border: 1px solid #ddd; padding: 0.2em 0.8em; text-align: left; }
+td.answer { font-family: monospace; }
+.ok { color: #080; }
+.failed { color: #b00; }
+svg { max-width: 100%; max-height: 40em; border: 1px solid #ddd; }
+";
+
+#[derive(Debug)]
+struct DayReport {
+    day: String,
+    examples: Result<TestSummary, String>,
+    run: Result<DayRun, String>,
+    svg: Option<String>,
+}
+
+// Runs every day's examples and real input, collecting the visualisations the days
+// write into a scratch directory, and writes everything into one HTML page.
+pub fn report(output: &Path) -> std::io::Result<()> {
+    let root = day::workspace_root();
+    let svg_dir = std::env::temp_dir().join(format!("aoc-report-{}", std::process::id()));
+    std::fs::create_dir_all(&svg_dir)?;
+    let mut reports = Vec::new();
+    for day in (1..=25).map(|n| format!("day{:02}", n)) {
+        eprintln!("running {}", day);
+        let examples = day::run_examples(&root, &day);
+        let run = day::run_day(&root, &day, &["--no-cache"], &[("AOC_SVG_DIR", &svg_dir)]);
+        let svg = std::fs::read_to_string(svg_dir.join(format!("{}.svg", day))).ok();
+        reports.push(DayReport {
+            day,
+            examples,
+            run,
+            svg,
+        });
+    }
+    std::fs::remove_dir_all(&svg_dir)?;
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output, render(&reports))?;
+    println!("wrote {}", output.display());
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn timing(run: &DayRun, step: &str) -> String {
+    run.timings
+        .get(step)
+        .map_or("—".to_string(), |took| format!("{:.2?}", took))
+}
+
+fn render_day(report: &DayReport) -> String {
+    let mut html = format!("<section id=\"{0}\">\n<h2>{0}</h2>\n", report.day);
+    match &report.examples {
+        Ok(summary) if summary.failed == 0 => {
+            html += &format!("<p class=\"ok\">examples: {} passed</p>\n", summary.passed)
+        }
+        Ok(summary) => {
+            html += &format!(
+                "<p class=\"failed\">examples: {} passed, {} failed</p>\n",
+                summary.passed, summary.failed
+            )
+        }
+        Err(err) => {
+            html += &format!(
+                "<p class=\"failed\">examples failed to build</p>\n<pre>{}</pre>\n",
+                escape(err)
+            )
+        }
+    }
+    match &report.run {
+        Ok(run) => {
+            html += "<table>\n<tr><th>part</th><th>answer</th></tr>\n";
+            for (part, answer) in &run.answers {
+                html += &format!(
+                    "<tr><td>{}</td><td class=\"answer\">{}</td></tr>\n",
+                    escape(part),
+                    escape(answer)
+                );
+            }
+            // Most days parse inside each part, so only a day timing its own parse gets a
+            // parse column.
+            let mut steps = vec![("one", "part one"), ("two", "part two")];
+            if run.timings.contains_key("parse") {
+                steps.insert(0, ("parse", "parse"));
+            }
+            html += "</table>\n<table>\n<tr>";
+            for (_, heading) in &steps {
+                html += &format!("<th>{}</th>", heading);
+            }
+            html += "</tr>\n<tr>";
+            for (step, _) in &steps {
+                html += &format!("<td>{}</td>", timing(run, step));
+            }
+            html += "</tr>\n</table>\n";
+        }
+        Err(err) => {
+            html += &format!(
+                "<p class=\"failed\">real input failed</p>\n<pre>{}</pre>\n",
+                escape(err)
+            )
+        }
+    }
+    if let Some(svg) = &report.svg {
+        html += svg;
+        html += "\n";
+    }
+    html + "</section>\n"
+}
+
+fn render(reports: &[DayReport]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Advent of Code 2024</title>
+<style>
+{}</style>
+</head>
+<body>
+<h1>Advent of Code 2024</h1>
+<p>Timings are from release builds.</p>
+",
+        STYLE
+    );
+    for report in reports {
+        html += &render_day(report);
+    }
+    html + "</body>\n</html>\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn day(run: Result<DayRun, String>, svg: Option<&str>) -> DayReport {
+        DayReport {
+            day: "day06".to_string(),
+            examples: Ok(TestSummary {
+                passed: 2,
+                failed: 0,
+            }),
+            run,
+            svg: svg.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn renders_answers_and_timings() {
+        let run = DayRun {
+            answers: vec![
+                ("one".to_string(), "41".to_string()),
+                ("two".to_string(), "<a&b>".to_string()),
+            ],
+            timings: [("one".to_string(), Duration::from_micros(1500))].into(),
+            total: Duration::ZERO,
+        };
+        let html = render_day(&day(Ok(run), Some("<svg></svg>")));
+        assert!(html.starts_with("<section id=\"day06\">\n<h2>day06</h2>\n"));
+        assert!(html.contains("<p class=\"ok\">examples: 2 passed</p>"));
+        assert!(html.contains("<td class=\"answer\">41</td>"));
+        assert!(html.contains("<td class=\"answer\">&lt;a&amp;b&gt;</td>"));
+        assert!(html.contains("<tr><th>part one</th><th>part two</th></tr>"));
+        assert!(html.contains("<tr><td>1.50ms</td><td>—</td></tr>"));
+        assert!(html.contains("<svg></svg>\n</section>"));
+    }
+
+    #[test]
+    fn renders_a_timed_parse() {
+        let run = DayRun {
+            answers: Vec::new(),
+            timings: [
+                ("parse".to_string(), Duration::from_micros(20)),
+                ("one".to_string(), Duration::from_micros(1500)),
+            ]
+            .into(),
+            total: Duration::ZERO,
+        };
+        let html = render_day(&day(Ok(run), None));
+        assert!(html.contains("<tr><th>parse</th><th>part one</th><th>part two</th></tr>"));
+        assert!(html.contains("<tr><td>20.00µs</td><td>1.50ms</td><td>—</td></tr>"));
+    }
+
+    #[test]
+    fn renders_failures() {
+        let mut report = day(Err("thread 'main' panicked".to_string()), None);
+        report.examples = Ok(TestSummary {
+            passed: 1,
+            failed: 1,
+        });
+        let html = render(&[report]);
+        assert!(html.contains("<p class=\"failed\">examples: 1 passed, 1 failed</p>"));
+        assert!(html.contains("<pre>thread 'main' panicked</pre>"));
+        assert!(!html.contains("<svg"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+}
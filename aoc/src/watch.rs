@@ -0,0 +1,211 @@
+use crate::day::{self, DayRun, TestSummary};
+use inotify::{Inotify, WatchMask};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+// How long to wait for further events once something changed, so that a save touching
+// several files (or an editor writing through a temporary file) triggers one run.
+const SETTLE: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Default)]
+struct Outcome {
+    examples: Option<TestSummary>,
+    run: Option<DayRun>,
+}
+
+pub fn watch(day: &str) -> std::io::Result<()> {
+    let root = day::workspace_root();
+    let mut inotify = Inotify::init()?;
+    let mask = WatchMask::CLOSE_WRITE | WatchMask::CREATE | WatchMask::DELETE | WatchMask::MOVED_TO;
+    for dir in [root.join(day).join("src"), root.join("utils").join("src")] {
+        inotify.watches().add(&dir, mask)?;
+        println!("watching {}", dir.display());
+    }
+
+    let mut previous = Outcome::default();
+    let mut buffer = [0; 4096];
+    loop {
+        previous = run_once(&root, day, previous);
+        let changed = wait_for_change(&mut inotify, &mut buffer)?;
+        println!("\n--- changed: {}", changed.join(", "));
+    }
+}
+
+fn is_relevant(name: &str) -> bool {
+    !(name.ends_with('~')
+        || name.ends_with(".swp")
+        || name.ends_with(".swx")
+        || name.starts_with(".#")
+        || name == "4913")
+}
+
+fn wait_for_change(inotify: &mut Inotify, buffer: &mut [u8]) -> std::io::Result<Vec<String>> {
+    let mut changed = Vec::new();
+    while changed.is_empty() {
+        for event in inotify.read_events_blocking(buffer)? {
+            if let Some(name) = event.name.and_then(|n| n.to_str()) {
+                if is_relevant(name) {
+                    changed.push(name.to_string());
+                }
+            }
+        }
+    }
+    loop {
+        std::thread::sleep(SETTLE);
+        match inotify.read_events(buffer) {
+            Ok(events) => {
+                let before = changed.len();
+                changed.extend(
+                    events
+                        .filter_map(|event| event.name?.to_str().map(str::to_string))
+                        .filter(|name| is_relevant(name)),
+                );
+                if changed.len() == before {
+                    break;
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+            Err(err) => return Err(err),
+        }
+    }
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+// Rebuilds, reruns the examples and the real input and reports what changed. Keeps the
+// last known results of whatever failed so the next successful run diffs against them.
+fn run_once(root: &Path, day: &str, previous: Outcome) -> Outcome {
+    if let Err(err) = day::build(root, day) {
+        println!("build failed:\n{}", err);
+        return previous;
+    }
+    let examples = match day::run_examples(root, day) {
+        Ok(summary) => {
+            println!(
+                "{}",
+                describe_examples(&summary, previous.examples.as_ref())
+            );
+            Some(summary)
+        }
+        Err(err) => {
+            println!("examples failed to build:\n{}", err);
+            previous.examples
+        }
+    };
+    let run = match day::run_day(root, day, &["--no-cache"], &[]) {
+        Ok(run) => {
+            for line in describe_run(&run, previous.run.as_ref()) {
+                println!("{}", line);
+            }
+            Some(run)
+        }
+        Err(err) => {
+            println!("real input failed:\n{}", err);
+            previous.run
+        }
+    };
+    Outcome { examples, run }
+}
+
+fn describe_examples(now: &TestSummary, before: Option<&TestSummary>) -> String {
+    let mut line = format!("examples: {} passed, {} failed", now.passed, now.failed);
+    if let Some(before) = before.filter(|before| *before != now) {
+        line += &format!(" (was {} passed, {} failed)", before.passed, before.failed);
+    }
+    line
+}
+
+fn describe_run(now: &DayRun, before: Option<&DayRun>) -> Vec<String> {
+    now.answers
+        .iter()
+        .map(|(part, answer)| {
+            let mut line = format!("part {}: ", part);
+            match before.and_then(|b| b.answer(part)) {
+                Some(old) if old != answer => line += &format!("{} -> {}", old, answer),
+                _ => line += answer,
+            }
+            if let Some(took) = now.timings.get(part) {
+                line += &format!("  {:.2?}", took);
+                if let Some(old) = before.and_then(|b| b.timings.get(part)) {
+                    line += &format!(" ({})", describe_timing(*took, *old));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+fn describe_timing(now: Duration, before: Duration) -> String {
+    if before.is_zero() {
+        return format!("was {:.2?}", before);
+    }
+    let change = (now.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("was {:.2?}, {:+.1}%", before, change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(answers: &[(&str, &str)], micros: &[(&str, u64)]) -> DayRun {
+        DayRun {
+            answers: answers
+                .iter()
+                .map(|(p, a)| (p.to_string(), a.to_string()))
+                .collect(),
+            timings: micros
+                .iter()
+                .map(|(p, us)| (p.to_string(), Duration::from_micros(*us)))
+                .collect(),
+            total: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn ignores_editor_files() {
+        assert!(is_relevant("main.rs"));
+        assert!(is_relevant("input.txt"));
+        assert!(!is_relevant("main.rs~"));
+        assert!(!is_relevant(".main.rs.swp"));
+        assert!(!is_relevant("4913"));
+    }
+
+    #[test]
+    fn diffs_against_previous_run() {
+        let before = run(
+            &[("one", "41"), ("two", "6")],
+            &[("one", 2000), ("two", 100)],
+        );
+        let now = run(&[("one", "41"), ("two", "7")], &[("one", 1000)]);
+        assert_eq!(
+            describe_run(&now, Some(&before)),
+            vec![
+                "part one: 41  1.00ms (was 2.00ms, -50.0%)",
+                "part two: 6 -> 7"
+            ]
+        );
+        assert_eq!(describe_run(&now, None)[0], "part one: 41  1.00ms");
+    }
+
+    #[test]
+    fn example_changes() {
+        let now = TestSummary {
+            passed: 2,
+            failed: 0,
+        };
+        let before = TestSummary {
+            passed: 1,
+            failed: 1,
+        };
+        assert_eq!(
+            describe_examples(&now, Some(&before)),
+            "examples: 2 passed, 0 failed (was 1 passed, 1 failed)"
+        );
+        assert_eq!(
+            describe_examples(&now, Some(&now)),
+            "examples: 2 passed, 0 failed"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "real-inputs")]
+
+// Runs every day on its input.txt and checks that it prints an answer for each part.
+// Only the days with an `[input]` table in their examples.toml have answers to compare
+// them with. Needs our own inputs:
+//
+//     cargo test -p aoc --features real-inputs
+
+use std::path::Path;
+use std::process::Command;
+
+fn run_day(root: &Path, day: &str) -> Result<String, String> {
+    let cargo = std::env::var("CARGO").unwrap_or("cargo".to_string());
+    // Release, as some days take minutes in debug. Without the cache, so that a stale
+    // answer does not hide a regression.
+    let output = Command::new(cargo)
+        .current_dir(root)
+        .args(["run", "-q", "--release", "-p", day, "--", "--no-cache"])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Day 25 has no second part.
+fn parts(day: &str) -> &'static [&'static str] {
+    if day == "day25" {
+        &["one"]
+    } else {
+        &["one", "two"]
+    }
+}
+
+fn check_day(root: &Path, day: &str, answers: &[(String, String)]) -> Vec<String> {
+    let input = root.join(day).join("src").join("input.txt");
+    if std::fs::metadata(&input).map_or(true, |metadata| metadata.len() == 0) {
+        return vec![format!("{}: {} is missing or empty", day, input.display())];
+    }
+    let stdout = match run_day(root, day) {
+        Ok(stdout) => stdout,
+        Err(err) => return vec![format!("{}: failed to run\n{}", day, err)],
+    };
+    parts(day)
+        .iter()
+        .filter_map(|part| {
+            let prefix = format!("{} part {}: ", day, part);
+            let actual = stdout.lines().find_map(|line| line.strip_prefix(&prefix));
+            let expected = answers
+                .iter()
+                .find(|(answered, _)| answered == part)
+                .map(|(_, answer)| answer.as_str());
+            match (expected, actual) {
+                (_, None) => Some(format!("{} part {}: printed no answer", day, part)),
+                (Some(expected), Some(actual)) if actual != expected => Some(format!(
+                    "{} part {}: expected {}, got {}",
+                    day, part, expected, actual
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn real_answers() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let mut failures = Vec::new();
+    for day in (1..=25).map(|n| format!("day{:02}", n)) {
+        let path = root.join(&day).join("src").join("examples.toml");
+        let answers = match std::fs::read_to_string(&path) {
+            Ok(manifest) => utils::examples::input_answers(&manifest)
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e)),
+            Err(_) => Vec::new(),
+        };
+        failures.extend(check_day(root, &day, &answers));
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
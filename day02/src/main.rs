@@ -1,21 +1,88 @@
-fn is_valid(report: &Vec<i32>) -> bool {
-    report
-        .windows(2)
-        .all(|w| 1 <= (w[1] - w[0]) && 3 >= (w[1] - w[0]))
-        || report
-            .windows(2)
-            .all(|w| 1 <= (w[0] - w[1]) && 3 >= (w[0] - w[1]))
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use utils::validate::{number, Diagnostics};
+
+// What makes a report safe: every step between kept levels has a size in `steps`, and
+// with `monotonic` they all go the same way.
+#[derive(Debug, Clone, PartialEq)]
+struct Rules {
+    steps: RangeInclusive<u32>,
+    monotonic: bool,
+}
+
+impl Rules {
+    // Whether removing at most `tolerance` levels makes the report safe, in one pass
+    // over it taking O(tolerance) per level.
+    fn is_safe(&self, report: &[i32], tolerance: usize) -> bool {
+        let signs: &[Option<i64>] = if self.monotonic {
+            &[Some(1), Some(-1)]
+        } else {
+            &[None]
+        };
+        signs.iter().any(|sign| {
+            let step_ok = |a: i32, b: i32| {
+                let step = b as i64 - a as i64;
+                match sign {
+                    Some(sign) => step * sign >= 0 && self.steps.contains(&((step * sign) as u32)),
+                    None => self.steps.contains(&(step.unsigned_abs() as u32)),
+                }
+            };
+            fewest_removals_at_most(report, tolerance, step_ok)
+        })
+    }
 }
 
-fn is_valid_p2(report: &Vec<i32>) -> bool {
-    for (i, _) in report.iter().enumerate() {
-        let mut perm = (*report).clone();
-        perm.remove(i);
-        if is_valid(&perm) {
-            return true;
+// Keeps, for each of the last `tolerance + 1` levels, the fewest removals before it that
+// leave valid steps up to it with it kept. A kept level can only follow one of those;
+// skipping more levels than that already removes too many. A tolerance beyond the
+// report's length keeps the whole report.
+fn fewest_removals_at_most(
+    report: &[i32],
+    tolerance: usize,
+    step_ok: impl Fn(i32, i32) -> bool,
+) -> bool {
+    let window_len = tolerance.min(report.len()) + 1;
+    let mut window: VecDeque<(i32, usize)> = VecDeque::with_capacity(window_len);
+    for (i, &level) in report.iter().enumerate() {
+        let mut fewest = i;
+        for (skipped, &(previous, removals)) in window.iter().rev().enumerate() {
+            if step_ok(previous, level) {
+                fewest = fewest.min(removals + skipped);
+            }
+        }
+        if window.len() == window_len {
+            window.pop_front();
         }
+        window.push_back((level, fewest));
     }
-    false
+    // The levels after the last kept one are removed as well.
+    report.is_empty()
+        || window
+            .iter()
+            .rev()
+            .enumerate()
+            .any(|(after, (_, removals))| removals + after <= tolerance)
+}
+
+// Tries every way of removing up to `tolerance` levels.
+fn is_safe_brute_force(rules: &Rules, report: &[i32], tolerance: usize) -> bool {
+    rules.is_safe(report, 0)
+        || (tolerance > 0
+            && (0..report.len()).any(|i| {
+                let mut rest = report.to_vec();
+                rest.remove(i);
+                is_safe_brute_force(rules, &rest, tolerance - 1)
+            }))
+}
+
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "report", |line| {
+        line.split_whitespace()
+            .map(number::<i32>)
+            .collect::<Result<Vec<_>, _>>()
+    });
+    diagnostics
 }
 
 fn parse_reports(input: &str) -> Vec<Vec<i32>> {
@@ -28,39 +95,168 @@ fn parse_reports(input: &str) -> Vec<Vec<i32>> {
         .collect()
 }
 
-fn part_two(input: &str) -> i32 {
-    let reports = parse_reports(input);
-    reports
+fn count_safe(input: &str, is_safe: impl Fn(&[i32]) -> bool) -> i32 {
+    parse_reports(input)
         .iter()
-        .filter(|report: &&Vec<i32>| is_valid_p2(*report))
+        .filter(|report| is_safe(report))
         .count() as i32
 }
 
-fn part_one(input: &str) -> i32 {
-    let reports = parse_reports(input);
-    reports
-        .iter()
-        .filter(|report: &&Vec<i32>| is_valid(*report))
-        .count() as i32
+fn part_two(input: &str, min_step: u32, max_step: u32, monotonic: bool, tolerance: usize) -> i32 {
+    let rules = Rules {
+        steps: min_step..=max_step,
+        monotonic,
+    };
+    count_safe(input, |report| rules.is_safe(report, tolerance))
+}
+
+fn part_two_brute_force(
+    input: &str,
+    min_step: u32,
+    max_step: u32,
+    monotonic: bool,
+    tolerance: usize,
+) -> i32 {
+    let rules = Rules {
+        steps: min_step..=max_step,
+        monotonic,
+    };
+    count_safe(input, |report| {
+        is_safe_brute_force(&rules, report, tolerance)
+    })
+}
+
+fn part_one(input: &str, min_step: u32, max_step: u32, monotonic: bool) -> i32 {
+    part_two(input, min_step, max_step, monotonic, 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("7 6 4\n1 2 x 4").problems(),
+            ["report 2: expected a number, found \"x\""]
+        );
+    }
+
+    fn puzzle() -> Rules {
+        Rules {
+            steps: 1..=3,
+            monotonic: true,
+        }
+    }
+
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 2);
+    fn removes_levels_anywhere() {
+        assert!(puzzle().is_safe(&[9, 1, 2, 3], 1));
+        assert!(puzzle().is_safe(&[1, 2, 3, 9], 1));
+        assert!(puzzle().is_safe(&[1, 5, 2, 3], 1));
+        assert!(!puzzle().is_safe(&[1, 9, 2, 8, 3], 1));
+        assert!(puzzle().is_safe(&[1, 9, 2, 8, 3], 2));
+        // A run of three unsafe levels in the middle has to go.
+        let report = [1, 2, 3, 4, 50, 60, 70, 5, 6, 7, 8];
+        assert!(!puzzle().is_safe(&report, 2));
+        assert!(puzzle().is_safe(&report, 3));
+        assert!(puzzle().is_safe(&[], 0));
+        assert!(puzzle().is_safe(&[5], 0));
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 4);
+    fn other_rules() {
+        let wiggly = Rules {
+            steps: 1..=5,
+            monotonic: false,
+        };
+        assert!(wiggly.is_safe(&[1, 6, 2, 7, 3], 0));
+        assert!(!wiggly.is_safe(&[1, 6, 6, 7, 3], 0));
+        assert!(wiggly.is_safe(&[1, 6, 6, 7, 3], 1));
+        let flat = Rules {
+            steps: 0..=0,
+            monotonic: true,
+        };
+        assert!(flat.is_safe(&[4, 4, 5, 4], 1));
+    }
+
+    #[test]
+    fn huge_tolerance() {
+        assert!(puzzle().is_safe(&[1, 9, 2, 8, 3], usize::MAX));
+        assert!(puzzle().is_safe(&[], usize::MAX));
+        assert_eq!(part_two("7 6 4 2 1\n1 2 7 8 9", 1, 3, true, usize::MAX), 2);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let rules = [
+            puzzle(),
+            Rules {
+                steps: 0..=2,
+                monotonic: false,
+            },
+        ];
+        let mut value = 3u64;
+        for _ in 0..300 {
+            let report = (0..8)
+                .map(|_| {
+                    value = value
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (value >> 40) as i32 % 8
+                })
+                .collect::<Vec<_>>();
+            for rules in &rules {
+                for tolerance in 0..=3 {
+                    assert_eq!(
+                        rules.is_safe(&report, tolerance),
+                        is_safe_brute_force(rules, &report, tolerance),
+                        "{:?} {:?} {}",
+                        rules,
+                        report,
+                        tolerance
+                    );
+                }
+            }
+        }
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param("min-step", 1u32, "smallest step allowed between levels")
+        .param("max-step", 3u32, "largest step allowed between levels")
+        .param(
+            "monotonic",
+            true,
+            "whether the levels have to keep increasing or keep decreasing",
+        )
+        .param("tolerance", 1usize, "levels part two may remove")
+        .from_env();
+    runner.validate(|| validate(input));
+    let (min_step, max_step) = (runner.get("min-step"), runner.get("max-step"));
+    let monotonic = runner.get("monotonic");
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input, min_step, max_step, monotonic))
+    );
+    let tolerance = runner.get("tolerance");
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner
+            .variants("two")
+            .variant("single-pass", || {
+                part_two(input, min_step, max_step, monotonic, tolerance)
+            })
+            .variant("brute-force", || {
+                part_two_brute_force(input, min_step, max_step, monotonic, tolerance)
+            })
+            .solve()
+    );
 }
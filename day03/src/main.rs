@@ -1,10 +1,41 @@
 use regex::Regex;
+use utils::validate::Diagnostics;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
+}
+
+// Any text is a program, but an empty one is more likely a missing input.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.check(!input.is_empty(), || "the memory is empty".to_string());
+    diagnostics
+}
+
+fn parse(input: &str) -> Vec<Instruction> {
+    let re = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)|(do\(\))|(don't\(\))").unwrap();
+    re.captures_iter(input)
+        .map(|caps| match &caps[0] {
+            "do()" => Instruction::Do,
+            "don't()" => Instruction::Dont,
+            _ => Instruction::Mul(
+                caps[1].parse::<i32>().unwrap(),
+                caps[2].parse::<i32>().unwrap(),
+            ),
+        })
+        .collect()
+}
 
 fn part_one(input: &str) -> i32 {
-    let re = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)").unwrap();
-    re.captures_iter(input).fold(0, |acc, caps| {
-        acc + &caps[1].parse::<i32>().unwrap() * &caps[2].parse::<i32>().unwrap()
-    })
+    parse(input)
+        .iter()
+        .fold(0, |acc, instruction| match instruction {
+            Instruction::Mul(a, b) => acc + a * b,
+            _ => acc,
+        })
 }
 
 #[derive(Debug)]
@@ -23,42 +54,52 @@ impl Acc {
 }
 
 fn part_two(input: &str) -> i32 {
-    let re = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)|(do\(\))|(don't\(\))").unwrap();
-    let res = re.captures_iter(input).fold(Acc::new(), |mut acc, caps| {
-        match &caps[0] {
-            "do()" => {
-                acc.enabled = true;
-            }
-            "don't()" => {
-                acc.enabled = false;
-            }
-            _ => {
-                if acc.enabled {
-                    acc.sum += &caps[1].parse::<i32>().unwrap() * &caps[2].parse::<i32>().unwrap();
+    let res = parse(input)
+        .iter()
+        .fold(Acc::new(), |mut acc, instruction| {
+            match instruction {
+                Instruction::Do => {
+                    acc.enabled = true;
+                }
+                Instruction::Dont => {
+                    acc.enabled = false;
+                }
+                Instruction::Mul(a, b) => {
+                    if acc.enabled {
+                        acc.sum += a * b;
+                    }
                 }
             }
-        }
-        acc
-    });
+            acc
+        });
     res.sum
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 161);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 48);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(validate("").problems(), ["the memory is empty"]);
     }
 }
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
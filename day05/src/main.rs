@@ -3,6 +3,37 @@ use petgraph::visit::IntoNodeIdentifiers;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::error::Error;
+use utils::validate::{number, Diagnostics};
+
+// `compare` looks every page of an update up among the rules, and the answer is the
+// middle page of each update.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some(sections) = diagnostics.sections(input, &["rules", "updates"]) else {
+        return diagnostics;
+    };
+    let rules = diagnostics.lines(sections[0], "rule", |line| {
+        let (before, after) = line
+            .split_once('|')
+            .ok_or_else(|| format!("expected \"<page>|<page>\", found {:?}", line))?;
+        Ok([number::<u32>(before)?, number::<u32>(after)?])
+    });
+    let ruled = rules.concat();
+    diagnostics.lines(sections[1], "update", |line| {
+        let pages = line
+            .split(',')
+            .map(number::<u32>)
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(page) = pages.iter().find(|page| !ruled.contains(page)) {
+            return Err(format!("page {} is in no rule", page));
+        }
+        if pages.len() % 2 == 0 {
+            return Err(format!("{} pages have no middle one", pages.len()));
+        }
+        Ok(())
+    });
+    diagnostics
+}
 
 fn parse(input: &str) -> (petgraph::Graph<u32, ()>, Vec<Vec<u32>>) {
     let (page_ordering_input, update_pages_input) = input.split_once("\n\n").unwrap();
@@ -122,20 +153,40 @@ fn part_two(input: &str) -> i32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 143);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 123);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("47|53\n97|47\n97-13\n\n47,53,97\n47,99,53\n47,53").problems(),
+            [
+                "rule 3: expected \"<page>|<page>\", found \"97-13\"",
+                "update 2: page 99 is in no rule",
+                "update 3: 2 pages have no middle one"
+            ]
+        );
+        assert_eq!(
+            validate("47|53").problems(),
+            ["expected rules and updates separated by a blank line, found 1 section"]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
@@ -1,6 +1,12 @@
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::ops::Add;
+use std::rc::Rc;
+use utils::bitgrid::BitGrid;
+use utils::checked::Checked;
+use utils::sim::{hash_of, Simulation};
+use utils::validate::Diagnostics;
 
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Ord, PartialOrd)]
 struct Coord(i32, i32);
@@ -13,56 +19,144 @@ impl Add for &Coord {
     }
 }
 
-fn has_obstacle(coord: &Coord, matrix: &Vec<Vec<char>>) -> bool {
-    matrix
-        .get(coord.0 as usize)
-        .map(|r| r.get(coord.1 as usize))
-        .unwrap_or(Some(&'.'))
-        .unwrap_or(&'.')
-        == &'#'
+fn has_obstacle(coord: &Coord, obstacles: &BitGrid) -> bool {
+    obstacles.get_coord(&utils::coord::Coord::new(coord.0, coord.1))
 }
 
-fn get_path_no_cycle(input: &str) -> Vec<(Coord, Coord)> {
-    let matrix = utils::matrix::parse_matrix(input);
-    let mut guard_coord = Coord(0i32, 0i32);
-    let mut max_i = 0;
-    let mut max_j = 0;
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            if matrix[i][j] == '^' {
-                guard_coord = Coord(i as i32, j as i32);
-            }
-            max_j = std::cmp::max(max_j, j as i32);
-        }
-        max_i = std::cmp::max(max_i, i as i32);
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    if let Some(grid) = diagnostics.grid(input, ".#^") {
+        diagnostics.exactly_one(&grid, '^', "guard");
     }
-    let mut path = Vec::new();
-    let mut diff = Coord(-1, 0);
+    diagnostics
+}
 
-    loop {
-        let next_guard_cord = &guard_coord + &diff;
-        if next_guard_cord.0 < 0 || next_guard_cord.1 < 0 {
-            path.push((guard_coord, diff));
-            break;
+fn turn_right(diff: &Coord) -> Coord {
+    match diff {
+        Coord(-1, 0) => Coord(0, 1),
+        Coord(0, 1) => Coord(1, 0),
+        Coord(1, 0) => Coord(0, -1),
+        Coord(0, -1) => Coord(-1, 0),
+        Coord(_, _) => {
+            panic!("Invalid direction")
         }
-        if next_guard_cord.0 > max_i || next_guard_cord.1 > max_j {
-            path.push((guard_coord, diff));
-            break;
+    }
+}
+
+#[derive(Clone)]
+struct Guard<'a> {
+    obstacles: &'a BitGrid,
+    coord: Coord,
+    diff: Coord,
+    left_map: bool,
+    moves: usize,
+    // The move that first reached each cell. Snapshots share it rather than each cloning
+    // a set of visited cells: the guard walks the same way again after a rewind, so a
+    // snapshot only counts the cells reached by its own move.
+    first_visits: Rc<RefCell<HashMap<Coord, usize>>>,
+}
+
+impl<'a> Guard<'a> {
+    fn new(matrix: &[Vec<char>], obstacles: &'a BitGrid) -> Self {
+        let (i, j) = (0..matrix.len())
+            .flat_map(|i| (0..matrix[i].len()).map(move |j| (i, j)))
+            .find(|(i, j)| matrix[*i][*j] == '^')
+            .unwrap();
+        let coord = Coord(i as i32, j as i32);
+        Guard {
+            obstacles,
+            coord,
+            diff: Coord(-1, 0),
+            left_map: false,
+            moves: 0,
+            first_visits: Rc::new(RefCell::new(HashMap::from([(coord, 0)]))),
         }
+    }
+
+    fn next_coord(&self) -> Option<Coord> {
+        let next = &self.coord + &self.diff;
+        let on_map = next.0 >= 0
+            && next.1 >= 0
+            && next.0 < self.obstacles.rows() as i32
+            && next.1 < self.obstacles.cols() as i32;
+        on_map.then_some(next)
+    }
+}
 
-        if !has_obstacle(&next_guard_cord, &matrix) {
-            path.push((guard_coord, diff));
-            guard_coord = next_guard_cord;
-        } else {
-            diff = match diff {
-                Coord(-1, 0) => Coord(0, 1),
-                Coord(0, 1) => Coord(1, 0),
-                Coord(1, 0) => Coord(0, -1),
-                Coord(0, -1) => Coord(-1, 0),
-                Coord(_, _) => {
-                    panic!("Invalid direction")
-                }
+impl Simulation for Guard<'_> {
+    // Walks on, turns right in front of an obstacle, or leaves the map.
+    fn step(&mut self) {
+        match self.next_coord() {
+            None => self.left_map = true,
+            Some(next) if !has_obstacle(&next, self.obstacles) => {
+                self.coord = next;
+                self.moves += 1;
+                self.first_visits
+                    .borrow_mut()
+                    .entry(next)
+                    .or_insert(self.moves);
             }
+            Some(_) => self.diff = turn_right(&self.diff),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.left_map
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&(self.coord, self.diff, self.left_map))
+    }
+
+    fn render(&self) -> String {
+        let guard = match self.diff {
+            Coord(-1, 0) => '^',
+            Coord(0, 1) => '>',
+            Coord(1, 0) => 'v',
+            _ => '<',
+        };
+        (0..self.obstacles.rows())
+            .map(|i| {
+                (0..self.obstacles.cols())
+                    .map(|j| {
+                        if !self.left_map && self.coord == Coord(i as i32, j as i32) {
+                            guard
+                        } else if self.obstacles.get(i, j) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let first_visits = self.first_visits.borrow();
+        let visited = first_visits.values().filter(|m| **m <= self.moves).count();
+        vec![("visited", visited.to_string())]
+    }
+}
+
+fn parse(input: &str) -> (Vec<Vec<char>>, BitGrid) {
+    let matrix = utils::matrix::parse_matrix(input);
+    let obstacles = BitGrid::from_matrix(&matrix, |c| *c == '#');
+    (matrix, obstacles)
+}
+
+// Every position the guard leaves, with the direction it leaves in, up to and
+// including the one it walks off the map from.
+fn get_path_no_cycle(input: &str) -> Vec<(Coord, Coord)> {
+    let (matrix, obstacles) = parse(input);
+    let mut guard = Guard::new(&matrix, &obstacles);
+    let mut path = Vec::new();
+    while !guard.is_done() {
+        let (coord, diff) = (guard.coord, guard.diff);
+        guard.step();
+        if guard.coord != coord || guard.is_done() {
+            path.push((coord, diff));
         }
     }
     path
@@ -75,23 +169,15 @@ fn part_one(input: &str) -> i32 {
         .collect::<Vec<_>>();
     path.sort();
     path.dedup();
-    path.len() as i32
+    Checked::<i32>::from_usize(path.len()).get()
 }
 
-fn move_guard(guard_coord: &mut Coord, guard_dir: &mut Coord, matrix: &Vec<Vec<char>>) {
+fn move_guard(guard_coord: &mut Coord, guard_dir: &mut Coord, obstacles: &BitGrid) {
     let next_guard_cord = &*guard_coord + &*guard_dir;
 
-    let rotated_guard_dir = match guard_dir {
-        Coord(-1, 0) => Coord(0, 1),
-        Coord(0, 1) => Coord(1, 0),
-        Coord(1, 0) => Coord(0, -1),
-        Coord(0, -1) => Coord(-1, 0),
-        Coord(_, _) => {
-            panic!("Invalid direction")
-        }
-    };
+    let rotated_guard_dir = turn_right(guard_dir);
 
-    if !has_obstacle(&next_guard_cord, &matrix) {
+    if !has_obstacle(&next_guard_cord, obstacles) {
         guard_coord.0 = next_guard_cord.0;
         guard_coord.1 = next_guard_cord.1;
     } else {
@@ -103,35 +189,26 @@ fn move_guard(guard_coord: &mut Coord, guard_dir: &mut Coord, matrix: &Vec<Vec<c
 fn check_for_loop(
     max_i: i32,
     max_j: i32,
-    matrix: &Vec<Vec<char>>,
+    obstacles: &BitGrid,
     guard_init_coord: &(Coord, Coord),
 ) -> bool {
-    let mut fast_guard_coord = guard_init_coord.0;
-    let mut guard_coord = guard_init_coord.0;
-    let mut diff = guard_init_coord.1;
-    let mut fast_diff = guard_init_coord.1;
-    loop {
-        move_guard(&mut guard_coord, &mut diff, matrix);
-        move_guard(&mut fast_guard_coord, &mut fast_diff, matrix);
-        move_guard(&mut fast_guard_coord, &mut fast_diff, matrix);
-
-        if fast_guard_coord.0 < 0 || fast_guard_coord.1 < 0 {
-            return false;
-        }
-        if fast_guard_coord.0 > max_i || fast_guard_coord.1 > max_j {
-            return false;
+    utils::cycle::has_cycle(*guard_init_coord, |(guard_coord, diff)| {
+        let (mut guard_coord, mut diff) = (*guard_coord, *diff);
+        move_guard(&mut guard_coord, &mut diff, obstacles);
+        if guard_coord.0 < 0 || guard_coord.1 < 0 {
+            return None;
         }
-
-        if fast_guard_coord == guard_coord && fast_diff == diff {
-            return true;
+        if guard_coord.0 > max_i || guard_coord.1 > max_j {
+            return None;
         }
-    }
+        Some((guard_coord, diff))
+    })
 }
 
 fn part_two(input: &str) -> i32 {
-    let mut matrix = utils::matrix::parse_matrix(input);
-    let max_i = matrix.len() - 1;
-    let max_j = matrix[0].len() - 1;
+    let (_, mut obstacles) = parse(input);
+    let max_i = obstacles.rows() - 1;
+    let max_j = obstacles.cols() - 1;
     let coords_to_check = get_path_no_cycle(input);
 
     let mut filtered_coords_to_check: Vec<(Coord, Coord)> = Vec::new();
@@ -148,33 +225,77 @@ fn part_two(input: &str) -> i32 {
     for ctc in filtered_coords_to_check.windows(2) {
         let guard_coord = ctc[0];
         let possible_obstacle = ctc[1].0;
-        matrix[possible_obstacle.0 as usize][possible_obstacle.1 as usize] = '#';
-        if check_for_loop(max_i as i32, max_j as i32, &matrix, &guard_coord) {
+        obstacles.set(
+            possible_obstacle.0 as usize,
+            possible_obstacle.1 as usize,
+            true,
+        );
+        if check_for_loop(max_i as i32, max_j as i32, &obstacles, &guard_coord) {
             obstacles_that_make_cycle.insert(possible_obstacle);
         }
-        matrix[possible_obstacle.0 as usize][possible_obstacle.1 as usize] = '.';
+        obstacles.set(
+            possible_obstacle.0 as usize,
+            possible_obstacle.1 as usize,
+            false,
+        );
     }
 
-    obstacles_that_make_cycle.len() as i32
+    Checked::<i32>::from_usize(obstacles_that_make_cycle.len()).get()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
+    use utils::sim::{Driver, Stop};
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 41);
+    fn walks_and_rewinds() {
+        let example = include_str!("example.txt");
+        let matrix = utils::matrix::parse_matrix(example);
+        let obstacles = BitGrid::from_matrix(&matrix, |c| *c == '#');
+        let mut driver = Driver::new(Guard::new(&matrix, &obstacles)).with_history();
+        assert_eq!(driver.sim().render(), example.trim_end().to_string() + "\n");
+        driver.run(6);
+        assert_eq!(driver.sim().render().lines().nth(1), Some("....>....#"));
+        assert_eq!(driver.sim().counters(), [("visited", "6".to_string())]);
+        assert_eq!(driver.rewind(6), 6);
+        assert_eq!(driver.sim().render(), example.trim_end().to_string() + "\n");
+        assert_eq!(driver.sim().counters(), [("visited", "1".to_string())]);
+        driver.run(6);
+        assert_eq!(driver.sim().counters(), [("visited", "6".to_string())]);
+        assert_eq!(driver.finish(), Stop::Done);
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 6);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("..#\n.^.\n^..").problems(),
+            ["expected one guard ('^'), found 2 at (1, 1), (2, 0)"]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "tui")]
+    {
+        let (matrix, obstacles) = parse(input);
+        runner.tui(|| Guard::new(&matrix, &obstacles));
+    }
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
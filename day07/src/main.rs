@@ -1,3 +1,6 @@
+use utils::checked::Checked;
+use utils::validate::{number, Diagnostics};
+
 struct Operation {
     result: u64,
     operands: Vec<u64>,
@@ -5,7 +8,7 @@ struct Operation {
 
 impl Operation {
     fn munch(&self, curr_sum: u64, i: usize, operator: char, concat_enabled: bool) -> Option<u64> {
-        let mut sum = curr_sum;
+        let mut sum = Checked(curr_sum);
         if i >= self.operands.len() {
             return None;
         }
@@ -15,11 +18,12 @@ impl Operation {
             '*' => sum *= rhs,
             '|' => {
                 let sum_math =
-                    sum * u64::pow(10, f64::log(rhs as f64, 10f64).floor() as u32 + 1) + rhs;
+                    sum * Checked(10u64).pow(f64::log(rhs as f64, 10f64).floor() as u32 + 1) + rhs;
                 sum = sum_math;
             }
             _ => unreachable!(),
         };
+        let sum = sum.get();
         if sum > self.result {
             None
         } else if sum == self.result && i == self.operands.len() - 1 {
@@ -54,6 +58,21 @@ impl Operation {
     }
 }
 
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "equation", |line| {
+        let (result, operands) = line
+            .split_once(':')
+            .ok_or_else(|| format!("expected \"<result>: <operands>\", found {:?}", line))?;
+        number::<u64>(result)?;
+        operands
+            .trim()
+            .split(' ')
+            .try_for_each(|operand| number::<u64>(operand).map(|_| ()))
+    });
+    diagnostics
+}
+
 fn parse(input: &str) -> Vec<Operation> {
     input
         .lines()
@@ -75,43 +94,59 @@ fn parse(input: &str) -> Vec<Operation> {
 
 fn part_one(input: &str) -> u64 {
     let inputs = parse(input);
-    let mut sum = 0;
+    let mut sum = Checked(0);
     for operation in inputs {
         if operation.is_valid_recursive(false) {
             sum += operation.result;
         }
     }
-    sum
+    sum.get()
 }
 
 fn part_two(input: &str) -> u64 {
     let inputs = parse(input);
-    let mut sum = 0;
+    let mut sum = Checked(0);
     for operation in inputs {
         if operation.is_valid_recursive(true) {
             sum += operation.result;
         }
     }
-    sum
+    sum.get()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 3749);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 11387);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("190: 10 19\n3267 81 40 27\n83: 17 x\n21:").problems(),
+            [
+                "equation 2: expected \"<result>: <operands>\", found \"3267 81 40 27\"",
+                "equation 3: expected a number, found \"x\"",
+                "equation 4: expected a number, found \"\""
+            ]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ops::{Add, Mul, Sub};
+use utils::validate::Diagnostics;
 
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Ord, PartialOrd)]
 struct Coord(i32, i32);
@@ -68,7 +69,20 @@ impl Coord {
     }
 }
 
-fn part_one(input: &str) -> u64 {
+// Antennas are letters and digits; anything else would be taken for a frequency too.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let allowed = ('0'..='9')
+        .chain('a'..='z')
+        .chain('A'..='Z')
+        .chain(['.'])
+        .collect::<String>();
+    diagnostics.grid(input, &allowed);
+    diagnostics
+}
+
+// The antennas of each frequency, and the largest row and column of the map.
+fn parse_antennas(input: &str) -> (HashMap<char, Vec<Coord>>, i32, i32) {
     let matrix = utils::matrix::parse_matrix(input);
     let max_i = matrix.len() as i32 - 1;
     let max_j = matrix[0].len() as i32 - 1;
@@ -85,6 +99,11 @@ fn part_one(input: &str) -> u64 {
             }
         }
     }
+    (antenna_types, max_i, max_j)
+}
+
+fn part_one(input: &str) -> u64 {
+    let (antenna_types, max_i, max_j) = parse_antennas(input);
 
     let mut anti_nodes = HashSet::new();
 
@@ -107,22 +126,7 @@ fn part_one(input: &str) -> u64 {
 }
 
 fn part_two(input: &str) -> u64 {
-    let matrix = utils::matrix::parse_matrix(input);
-    let max_i = matrix.len() as i32 - 1;
-    let max_j = matrix[0].len() as i32 - 1;
-    let mut antenna_types = HashMap::new();
-    for i in 0..matrix.len() {
-        for j in 0..matrix[0].len() {
-            let char = matrix[i][j];
-            match char {
-                '.' => {}
-                x => antenna_types
-                    .entry(x)
-                    .or_insert(vec![])
-                    .push(Coord(i as i32, j as i32)),
-            }
-        }
-    }
+    let (antenna_types, max_i, max_j) = parse_antennas(input);
 
     let mut anti_nodes = HashSet::new();
 
@@ -149,23 +153,33 @@ fn part_two(input: &str) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
-
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 14);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE_SMALL), 9);
-        assert_eq!(part_two(EXAMPLE), 34);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert!(validate(include_str!("example_small.txt")).is_empty());
+        assert_eq!(
+            validate("..a.\n.#..\n...a").problems(),
+            ["unexpected '#' at (1, 1)"]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
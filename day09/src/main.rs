@@ -1,5 +1,19 @@
 use std::cmp::Ordering;
+use utils::validate::Diagnostics;
 
+// The disk map is a single line of digits, without even a trailing newline.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.check(!input.is_empty(), || "the disk map is empty".to_string());
+    for (i, c) in input.char_indices() {
+        diagnostics.check(c.is_ascii_digit(), || {
+            format!("unexpected {:?} at {}", c, i)
+        });
+    }
+    diagnostics
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 struct BlockData {
     size: u32,
@@ -22,6 +36,7 @@ impl BlockData {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug)]
 struct DiskMap {
     blocks: Vec<BlockData>,
@@ -57,7 +72,8 @@ impl DiskMap {
     }
 }
 
-fn part_one(input: &str) -> u64 {
+// Moves single blocks from the end into the leftmost free space.
+fn compact_blocks(input: &str) -> DiskMap {
     let mut disk_map = DiskMap::new(input);
     let mut left_pointer = 0;
     let mut right_pointer = disk_map.blocks.len() - 1;
@@ -96,11 +112,15 @@ fn part_one(input: &str) -> u64 {
         }
     }
 
-    let new_map = DiskMap { blocks: new_blocks };
-    new_map.calc_checksum()
+    DiskMap { blocks: new_blocks }
 }
 
-fn part_two(input: &str) -> u64 {
+fn part_one(input: &str) -> u64 {
+    compact_blocks(input).calc_checksum()
+}
+
+// Moves whole files, from the last one, into the leftmost free space that fits them.
+fn compact_files(input: &str) -> DiskMap {
     let mut disk_map = DiskMap::new(input);
     let mut left_pointer = 0;
     let mut new_blocks: Vec<BlockData> = Vec::new();
@@ -131,30 +151,45 @@ fn part_two(input: &str) -> u64 {
         disk_map.blocks[left_pointer].size = free_space - block_size;
     }
 
-    let new_map = DiskMap { blocks: new_blocks };
-    new_map.calc_checksum()
+    DiskMap { blocks: new_blocks }
+}
+
+fn part_two(input: &str) -> u64 {
+    compact_files(input).calc_checksum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const INPUT: &str = include_str!("input.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 1928);
-        assert_eq!(part_one(INPUT), 6382875730645);
-    }
-
-    #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 2858);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(validate("12345\n").problems(), ["unexpected '\\n' at 5"]);
+        assert_eq!(validate("").problems(), ["the disk map is empty"]);
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "serde")]
+    {
+        runner.dump_state("blocks-compacted", || compact_blocks(input));
+        runner.dump_state("files-compacted", || compact_files(input));
+    }
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
@@ -1,10 +1,9 @@
-use petgraph::dot::{Config, Dot};
-use petgraph::graph::DiGraph;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::{Dfs, EdgeRef, Reversed};
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
-use std::process::Command;
+use utils::svg::Svg;
+use utils::validate::Diagnostics;
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Ord, PartialOrd)]
 struct Coord(i32, i32);
 
@@ -16,6 +15,12 @@ impl Add for Coord {
     }
 }
 
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.grid(input, "0123456789");
+    diagnostics
+}
+
 fn get_neighbour_indices(c: Coord) -> Vec<Coord> {
     let mut res = Vec::with_capacity(4);
     res.push(c + Coord(1, 0));
@@ -73,24 +78,65 @@ impl TopographicMap {
         }
     }
 
-    fn export_to_png(&self, filename: &str) {
-        let dot_data = format!(
-            "{:?}",
-            Dot::with_config(&self.graph, &[Config::EdgeNoLabel])
-        );
-        let mut file =
-            File::create(std::format!("{}.dot", filename)).expect("Error creating DOT file");
-        file.write_all(dot_data.as_bytes())
-            .expect("Error writing to DOT file");
-        Command::new("sh")
-            .arg("-c")
-            .arg(std::format!(
-                "dot -Tpng {}.dot -o {}.png",
-                filename,
-                filename
-            ))
-            .output()
-            .expect("failed to execute process");
+    // Cells on some trail: reachable uphill from a trailhead and leading up to a top.
+    fn trail_cells(&self) -> HashSet<NodeIndex> {
+        let no_cells = Vec::new();
+        let mut from_trailheads = HashSet::new();
+        let mut dfs = Dfs::empty(&self.graph);
+        for trailhead in self.height_map.get(&0).unwrap_or(&no_cells) {
+            dfs.move_to(self.node_indices[trailhead]);
+            while let Some(node) = dfs.next(&self.graph) {
+                from_trailheads.insert(node);
+            }
+        }
+        let reversed = Reversed(&self.graph);
+        let mut to_tops = HashSet::new();
+        let mut dfs = Dfs::empty(reversed);
+        for top in self.height_map.get(&9).unwrap_or(&no_cells) {
+            dfs.move_to(self.node_indices[top]);
+            while let Some(node) = dfs.next(reversed) {
+                to_tops.insert(node);
+            }
+        }
+        from_trailheads.intersection(&to_tops).copied().collect()
+    }
+
+    fn visualise(&self) -> String {
+        let rows = self.node_indices.keys().map(|c| c.0 + 1).max().unwrap_or(0);
+        let cols = self.node_indices.keys().map(|c| c.1 + 1).max().unwrap_or(0);
+        let mut svg = Svg::for_grid(rows as usize, cols as usize);
+        let mut coords = HashMap::new();
+        for (coord, node) in &self.node_indices {
+            let height = self.graph[*node];
+            let shade = 235 - height * 20;
+            svg.cell(coord.0, coord.1, &format!("rgb({0},{0},{0})", shade));
+            coords.insert(*node, *coord);
+        }
+        let centre = |node: NodeIndex| {
+            let Coord(i, j) = coords[&node];
+            (j as f64 + 0.5, i as f64 + 0.5)
+        };
+        let trail = self.trail_cells();
+        for edge in self.graph.edge_references() {
+            if trail.contains(&edge.source()) && trail.contains(&edge.target()) {
+                svg.line(
+                    centre(edge.source()),
+                    centre(edge.target()),
+                    "darkorange",
+                    0.15,
+                );
+            }
+        }
+        for (height, colour) in [(0, "green"), (9, "red")] {
+            for coord in self.height_map.get(&height).unwrap_or(&vec![]) {
+                let node = self.node_indices[coord];
+                if trail.contains(&node) {
+                    let (x, y) = centre(node);
+                    svg.circle(x, y, 0.3, colour);
+                }
+            }
+        }
+        svg.to_string()
     }
 }
 
@@ -133,24 +179,44 @@ fn part_two(input: &str) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
-    const INPUT: &str = include_str!("input.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE_SMALL), 2);
-        assert_eq!(part_one(EXAMPLE), 36);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("0123\n1.34").problems(),
+            ["unexpected '.' at (1, 1)"]
+        );
     }
 
+    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
+
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 81);
+    fn visualises_trails() {
+        let map = TopographicMap::new(EXAMPLE_SMALL);
+        assert_eq!(map.trail_cells().len(), 16);
+        let svg = map.visualise();
+        assert_eq!(svg.matches("<rect").count(), 49);
+        assert_eq!(svg.matches("<circle").count(), 3);
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    runner.visualise(|| TopographicMap::new(input).visualise());
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
@@ -1,4 +1,17 @@
 use std::collections::HashMap;
+use utils::validate::{number, Diagnostics};
+
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let stones = input.split_ascii_whitespace().collect::<Vec<_>>();
+    diagnostics.check(!stones.is_empty(), || "there are no stones".to_string());
+    for stone in stones {
+        if let Err(reason) = number::<u64>(stone) {
+            diagnostics.report(reason);
+        }
+    }
+    diagnostics
+}
 
 fn transform_stone(stone: u64) -> Vec<u64> {
     match stone {
@@ -32,37 +45,45 @@ fn blink_at_stone(stone: u64, blinks: usize, memo: &mut HashMap<(u64, usize), u6
     count
 }
 
-fn part_one(input: &str) -> u64 {
-    let stones = input
+fn parse_stones(input: &str) -> Vec<u64> {
+    input
         .split_ascii_whitespace()
         .map(|x_str| x_str.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
-    let mut cache = HashMap::new();
-    let mut sum = 0;
-    for stone in stones {
-        sum += blink_at_stone(stone, 25, &mut cache);
-    }
-    sum
+        .collect()
 }
 
-fn part_two(input: &str) -> u64 {
-    let stones = input
-        .split_ascii_whitespace()
-        .map(|x_str| x_str.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
+fn solver(input: &str, blinks: usize) -> u64 {
+    let stones = parse_stones(input);
     let mut cache = HashMap::new();
     let mut sum = 0;
     for stone in stones {
-        sum += blink_at_stone(stone, 75, &mut cache);
+        sum += blink_at_stone(stone, blinks, &mut cache);
     }
     sum
 }
 
+fn part_one(input: &str, blinks: usize) -> u64 {
+    solver(input, blinks)
+}
+
+fn part_two(input: &str, blinks: usize) -> u64 {
+    solver(input, blinks)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const INPUT: &str = include_str!("input.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("125 -17\n").problems(),
+            ["expected a number, found \"-17\""]
+        );
+        assert_eq!(validate("\n").problems(), ["there are no stones"]);
+    }
 
     #[test]
     fn test_stone_split() {
@@ -72,21 +93,24 @@ mod tests {
         assert_eq!(transform_stone(99), [9, 9]);
         assert_eq!(transform_stone(999), [2021976]);
     }
-
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 55312);
-        assert_eq!(part_one(INPUT), 187738);
-    }
-
-    #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 0);
-    }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param("blinks-one", 25usize, "number of blinks in part one")
+        .param("blinks-two", 75usize, "number of blinks in part two")
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input, runner.get("blinks-one")))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input, runner.get("blinks-two")))
+    );
 }
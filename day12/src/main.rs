@@ -1,44 +1,57 @@
+#[cfg(feature = "graphviz")]
 use petgraph::dot::{Config, Dot};
+#[cfg(feature = "graphviz")]
 use petgraph::graph::Graph;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
+#[cfg(feature = "graphviz")]
+use std::collections::HashMap;
+#[cfg(feature = "graphviz")]
 use std::io::Write;
-use std::ops::Add;
-use std::process::Command;
-#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Ord, PartialOrd)]
-struct Coord(i32, i32);
-
-impl Add for Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Coord(self.0 + rhs.0, self.1 + rhs.1)
-    }
+use utils::coord::Coord;
+use utils::dsu::Dsu;
+use utils::region::Region;
+use utils::svg::Svg;
+use utils::validate::Diagnostics;
+
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.grid(input, "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    diagnostics
 }
 
-fn get_neighbours(c: Coord) -> Vec<Coord> {
-    let mut res = Vec::with_capacity(4);
-    res.push(c + Coord(1, 0));
-    res.push(c + Coord(0, 1));
-    res.push(c + Coord(-1, 0));
-    res.push(c + Coord(0, -1));
-    res
-}
-
-fn get_corners(c: Coord) -> [[Coord; 3]; 4] {
-    [
-        [c + Coord(1, 0), c + Coord(1, 1), c + Coord(0, 1)],
-        [c + Coord(0, 1), c + Coord(-1, 1), c + Coord(-1, 0)],
-        [c + Coord(-1, 0), c + Coord(-1, -1), c + Coord(0, -1)],
-        [c + Coord(0, -1), c + Coord(1, -1), c + Coord(1, 0)],
-    ]
+fn get_regions(input: &str) -> Vec<Region> {
+    let matrix = utils::matrix::parse_matrix(input);
+    let cols = matrix.first().map_or(0, Vec::len);
+    let mut fields = Dsu::new(matrix.len() * cols);
+    for i in 0..matrix.len() {
+        for j in 0..cols {
+            if i + 1 < matrix.len() && matrix[i + 1][j] == matrix[i][j] {
+                fields.union(i * cols + j, (i + 1) * cols + j);
+            }
+            if j + 1 < cols && matrix[i][j + 1] == matrix[i][j] {
+                fields.union(i * cols + j, i * cols + j + 1);
+            }
+        }
+    }
+    fields
+        .components()
+        .into_iter()
+        .map(|field| {
+            field
+                .into_iter()
+                .map(|c| Coord::new((c / cols) as i32, (c % cols) as i32))
+                .collect::<Region>()
+        })
+        .collect()
 }
 
+// The plots as a graph joined along same-crop borders, drawn with Graphviz by the
+// `graphviz` feature.
+#[cfg(feature = "graphviz")]
 struct Fields {
     graph: Graph<(char, Coord), i32>,
-    node_indices: HashMap<Coord, petgraph::graph::NodeIndex>,
 }
 
+#[cfg(feature = "graphviz")]
 impl Fields {
     fn new(input: &str) -> Self {
         let matrix = utils::matrix::parse_matrix(input);
@@ -48,34 +61,30 @@ impl Fields {
         matrix.iter().enumerate().for_each(|(i, row)| {
             row.iter().enumerate().for_each(|(j, char)| {
                 node_indices.insert(
-                    Coord(i as i32, j as i32),
-                    graph.add_node((*char, Coord(i as i32, j as i32))),
+                    Coord::new(i as i32, j as i32),
+                    graph.add_node((*char, Coord::new(i as i32, j as i32))),
                 );
             })
         });
 
-        for i in 0..matrix.len() {
-            for j in 0..matrix[i].len() {
-                let coord = Coord(i as i32, j as i32);
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, crop_type) in row.iter().enumerate() {
+                let coord = Coord::new(i as i32, j as i32);
                 let coord_id = node_indices.get(&coord).unwrap();
-                let crop_type = matrix[i][j];
-                for neighbour in get_neighbours(coord) {
+                for neighbour in coord.get_neighbours() {
                     let neighbour_node_id = node_indices.get(&neighbour);
                     if neighbour_node_id.is_none() {
                         continue;
                     }
                     let (neighbour_crop_type, _) =
                         *graph.node_weight(*neighbour_node_id.unwrap()).unwrap();
-                    if crop_type == neighbour_crop_type {
+                    if *crop_type == neighbour_crop_type {
                         graph.add_edge(*neighbour_node_id.unwrap(), *coord_id, 1);
                     }
                 }
             }
         }
-        Self {
-            graph,
-            node_indices,
-        }
+        Self { graph }
     }
 
     fn export_to_png(&self, filename: &str) {
@@ -83,11 +92,11 @@ impl Fields {
             "{:?}",
             Dot::with_config(&self.graph, &[Config::EdgeNoLabel])
         );
-        let mut file =
-            File::create(std::format!("{}.dot", filename)).expect("Error creating DOT file");
+        let mut file = std::fs::File::create(std::format!("{}.dot", filename))
+            .expect("Error creating DOT file");
         file.write_all(dot_data.as_bytes())
             .expect("Error writing to DOT file");
-        Command::new("sh")
+        std::process::Command::new("sh")
             .arg("-c")
             .arg(std::format!(
                 "dot -Tpng {}.dot -o {}.png",
@@ -99,95 +108,122 @@ impl Fields {
     }
 }
 
+// What both parts price a region by, for `--dump-state`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RegionReport {
+    plant: char,
+    area: usize,
+    perimeter: usize,
+    sides: usize,
+    region: Region,
+}
+
+#[cfg(feature = "serde")]
+fn region_reports(input: &str) -> Vec<RegionReport> {
+    let matrix = utils::matrix::parse_matrix(input);
+    get_regions(input)
+        .into_iter()
+        .map(|region| {
+            let cell = region.cells().next().unwrap();
+            RegionReport {
+                plant: matrix[cell.x as usize][cell.y as usize],
+                area: region.area(),
+                perimeter: region.perimeter(),
+                sides: region.sides(),
+                region,
+            }
+        })
+        .collect()
+}
+
 fn part_one(input: &str) -> u64 {
-    let map = Fields::new(input);
-    let crop_fields = petgraph::algo::tarjan_scc(&map.graph);
-    let mut sum = 0;
-    for crop_field in crop_fields {
-        let mut area = 0;
-        let mut perimeter = 0;
-        for crop_node_index in crop_field {
-            let crop_node_edges = map.graph.edges(crop_node_index);
-            perimeter += 4 - crop_node_edges.count();
-            area += 1;
-        }
-        sum += area * perimeter;
-    }
-    //map.export_to_png("day12");
-    sum as u64
+    get_regions(input)
+        .iter()
+        .map(|region| (region.area() * region.perimeter()) as u64)
+        .sum()
 }
 
 fn part_two(input: &str) -> u64 {
-    let map = Fields::new(input);
-    let crop_fields = petgraph::algo::tarjan_scc(&map.graph);
-    let mut sum = 0;
-    for crop_field in crop_fields {
-        let mut area = 0;
-        let mut break_points = HashSet::new();
-        let mut trickies = 0;
-        let coords_of_crop_field = crop_field
-            .iter()
-            .map(|c| map.graph.node_weight(*c).unwrap().1)
-            .collect::<Vec<_>>();
-        for crop_node_index in crop_field {
-            let crop_node_coord = map.graph.node_weight(crop_node_index).unwrap().1;
-            get_corners(crop_node_coord).iter().for_each(|c| {
-                let is_0_crop = coords_of_crop_field.contains(&c[0]);
-                let is_1_crop = coords_of_crop_field.contains(&c[1]);
-                let is_2_crop = coords_of_crop_field.contains(&c[2]);
-
-                let breaks = match (is_0_crop, is_1_crop, is_2_crop) {
-                    (false, false, true) => false,
-                    (true, false, false) => false,
-                    (true, true, true) => false,
-                    (false, true, false) => {
-                        trickies += 1;
-                        true
-                    }
-                    (_, _, _) => true,
-                };
-                let x = c[0].0 + c[1].0 + c[2].0 + crop_node_coord.0;
-                let y = c[0].1 + c[1].1 + c[2].1 + crop_node_coord.1;
-                if breaks {
-                    break_points.insert((x, y));
-                }
-            });
+    get_regions(input)
+        .iter()
+        .map(|region| (region.area() * region.sides()) as u64)
+        .sum()
+}
 
-            area += 1;
+fn visualise(input: &str) -> String {
+    let regions = get_regions(input);
+    let rows = input.lines().count();
+    let cols = input.lines().next().map_or(0, str::len);
+    let mut svg = Svg::for_grid(rows, cols);
+    for (i, region) in regions.iter().enumerate() {
+        let colour = utils::svg::palette(i);
+        for cell in region.cells() {
+            svg.cell(cell.x, cell.y, &colour);
+        }
+    }
+    for region in &regions {
+        for outline in region.outlines() {
+            let vertices = outline
+                .vertices
+                .iter()
+                .map(|v| (v.y as f64, v.x as f64))
+                .collect::<Vec<_>>();
+            svg.polygon(&vertices, "none", "black", 0.1);
         }
-        assert!(break_points.len() >= 4);
-        sum += area * (break_points.len() + trickies / 2) as u64;
     }
-    sum
+    svg.to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
-    const EXAMPLE_E: &str = include_str!("example_e.txt");
-    const EXAMPLE_TRICKY: &str = include_str!("example_tricky.txt");
-
-    const INPUT: &str = include_str!("input.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE_SMALL), 140);
-        assert_eq!(part_one(EXAMPLE), 1930);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("AAB\nA.B").problems(),
+            ["unexpected '.' at (1, 1)"]
+        );
+        assert_eq!(
+            validate("AAB\nAB").problems(),
+            ["the grid is not rectangular: row 1 has 2 columns, expected 3"]
+        );
     }
 
+    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
+
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE_SMALL), 80);
-        assert_eq!(part_two(EXAMPLE_E), 236);
-        assert_eq!(part_two(EXAMPLE), 1206);
-        assert_eq!(part_two(EXAMPLE_TRICKY), 368);
+    fn visualises_regions() {
+        let svg = visualise(EXAMPLE_SMALL);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 16);
+        assert_eq!(svg.matches("<polygon").count(), 5);
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    runner.visualise(|| visualise(input));
+    // Writes day12.dot and, with Graphviz installed, day12.png.
+    #[cfg(feature = "graphviz")]
+    Fields::new(input).export_to_png(env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "serde")]
+    runner.dump_state("regions", || region_reports(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
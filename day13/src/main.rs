@@ -1,5 +1,10 @@
 use regex::Regex;
+use utils::checked::Checked;
+use utils::validate::{number, Diagnostics};
 
+const CLAW_MACHINE_REGEX: &str = "^Button A: X\\+(?<a_x>.*), Y\\+(?<a_y>.*)\nButton B: X\\+(?<b_x>.*), Y\\+(?<b_y>.*)\nPrize: X=(?<p_x>.*), Y=(?<p_y>.*)$";
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug)]
 struct ClawMachine {
     button_a_x_movement: i128,
@@ -13,16 +18,15 @@ struct ClawMachine {
 
 impl ClawMachine {
     fn new(input: &str, prize_offset: i128) -> ClawMachine {
-        let claw_machine_regex = "^Button A: X\\+(?<a_x>.*), Y\\+(?<a_y>.*)\nButton B: X\\+(?<b_x>.*), Y\\+(?<b_y>.*)\nPrize: X=(?<p_x>.*), Y=(?<p_y>.*)$";
-        let re = Regex::new(claw_machine_regex).expect("invalid regex");
+        let re = Regex::new(CLAW_MACHINE_REGEX).expect("invalid regex");
         let captures = re.captures(input).unwrap();
         Self {
             button_a_x_movement: *&captures["a_x"].parse::<i128>().unwrap(),
             button_a_y_movement: *&captures["a_y"].parse::<i128>().unwrap(),
             button_b_x_movement: *&captures["b_x"].parse::<i128>().unwrap(),
             button_b_y_movement: *&captures["b_y"].parse::<i128>().unwrap(),
-            prize_x: *&captures["p_x"].parse::<i128>().unwrap() + prize_offset,
-            prize_y: *&captures["p_y"].parse::<i128>().unwrap() + prize_offset,
+            prize_x: (Checked(*&captures["p_x"].parse::<i128>().unwrap()) + prize_offset).get(),
+            prize_y: (Checked(*&captures["p_y"].parse::<i128>().unwrap()) + prize_offset).get(),
         }
     }
 
@@ -34,36 +38,34 @@ impl ClawMachine {
     }
 }
 
-fn gaussian_elimination_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<[i128; 2]> {
-    let mut a = a.map(|row| row.map(|val| val as f64));
-    let mut b = b.map(|val| val as f64);
-
-    let factor = a[1][0] / a[0][0];
-    a[1][0] -= factor * a[0][0];
-    a[1][1] -= factor * a[0][1];
-
-    b[1] -= factor * b[0];
-
-    if a[1][1] == 0.0 {
-        return None;
-    }
-
-    let x2 = b[1] / a[1][1];
-    let x1 = (b[0] - a[0][1] * x2) / a[0][0];
-
-    let x1_i128 = x1.round();
-    let x2_i128 = x2.round();
-
-    if (x1 - x1_i128).abs() > 0.001 || (x2 - x2_i128).abs() > 0.001 {
-        return None;
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let re = Regex::new(CLAW_MACHINE_REGEX).expect("invalid regex");
+    for (i, machine) in input.split("\n\n").enumerate() {
+        let Some(captures) = re.captures(machine) else {
+            diagnostics.report(format!(
+                "machine {}: expected Button A, Button B and Prize lines",
+                i + 1
+            ));
+            continue;
+        };
+        for name in ["a_x", "a_y", "b_x", "b_y", "p_x", "p_y"] {
+            if let Err(reason) = number::<i128>(&captures[name]) {
+                diagnostics.report(format!("machine {}: {}", i + 1, reason));
+            }
+        }
     }
+    diagnostics
+}
 
-    Some([x1_i128 as i128, x2_i128 as i128])
+fn gaussian_elimination_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<[i128; 2]> {
+    let [x1, x2] = utils::math::solve_2x2(a, b)?;
+    Some([x1.to_integer()?, x2.to_integer()?])
 }
 
 fn solver(input: &str, prize_offset: i128) -> i128 {
     let machines = ClawMachine::parse_machines(input, prize_offset);
-    let mut sum = 0;
+    let mut sum = Checked(0);
     for machine in machines {
         if let Some(res) = gaussian_elimination_2x2(
             [
@@ -72,24 +74,41 @@ fn solver(input: &str, prize_offset: i128) -> i128 {
             ],
             [machine.prize_x, machine.prize_y],
         ) {
-            sum += res[0] * 3 + res[1] * 1;
+            sum += Checked(res[0]) * 3 + Checked(res[1]) * 1;
         }
     }
-    sum
+    sum.get()
 }
 
 fn part_one(input: &str) -> i128 {
     solver(input, 0)
 }
 
-fn part_two(input: &str) -> i128 {
-    solver(input, 10000000000000)
+fn part_two(input: &str, prize_offset: i128) -> i128 {
+    solver(input, prize_offset)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate(
+                "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\n\
+                 Button A: X+26, Y+66\nButton B: X+67, Y+x\nPrize: X=12748, Y=12176\n\n\
+                 Button A: X+17, Y+86\nPrize: X=7870, Y=6450"
+            )
+            .problems(),
+            [
+                "machine 2: expected a number, found \"x\"",
+                "machine 3: expected Button A, Button B and Prize lines"
+            ]
+        );
+    }
 
     #[test]
     fn gauss_elimination() {
@@ -142,20 +161,31 @@ mod tests {
             Some([102851800151, 107526881786])
         );
     }
-
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 480);
-    }
-
-    #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 875318608908);
-    }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param(
+            "prize-offset",
+            10000000000000i128,
+            "offset added to prize coordinates in part two",
+        )
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "serde")]
+    runner.dump_state("machines", || {
+        ClawMachine::parse_machines(input, runner.get("prize-offset"))
+    });
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input, runner.get("prize-offset")))
+    );
 }
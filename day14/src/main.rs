@@ -1,11 +1,51 @@
 use regex::Regex;
+use utils::bitgrid::BitGrid;
 use utils::coord::Coord;
+use utils::sim::{hash_of, Driver, Simulation, Stop};
+use utils::svg::Svg;
+use utils::validate::{number, Diagnostics};
 
+const ROBOT_REGEX: &str = "^p=(?<p_x>.*),(?<p_y>.*) v=(?<v_x>.*),(?<v_y>.*)$";
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Hash)]
 struct Robot {
     position: Coord,
     velocity: Coord,
 }
 
+// Robots start inside the space: `robot_grid` marks their cells before any step wraps
+// them around. Like `parse_robots`, every line is a robot, even a trailing empty one.
+fn validate(input: &str, max_x: usize, max_y: usize) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let re = Regex::new(ROBOT_REGEX).expect("invalid regex");
+    for (i, line) in input.split('\n').enumerate() {
+        let Some(captures) = re.captures(line) else {
+            diagnostics.report(format!(
+                "robot {}: expected \"p=<x>,<y> v=<dx>,<dy>\", found {:?}",
+                i + 1,
+                line
+            ));
+            continue;
+        };
+        let [x, y, dx, dy] =
+            ["p_x", "p_y", "v_x", "v_y"].map(|name| number::<i32>(&captures[name]));
+        for reason in [&x, &y, &dx, &dy]
+            .into_iter()
+            .filter_map(|n| n.as_ref().err())
+        {
+            diagnostics.report(format!("robot {}: {}", i + 1, reason));
+        }
+        if let (Ok(x), Ok(y)) = (x, y) {
+            diagnostics.check(
+                (0..=max_x as i32).contains(&x) && (0..=max_y as i32).contains(&y),
+                || format!("robot {}: starts at {},{} outside the space", i + 1, x, y),
+            );
+        }
+    }
+    diagnostics
+}
+
 fn wrap_range(input: i32, max: i32, min: i32) -> i32 {
     let modulus = max - min + 1;
     let in_group = input % modulus;
@@ -19,8 +59,7 @@ fn wrap_range(input: i32, max: i32, min: i32) -> i32 {
 
 impl Robot {
     fn new(line: &str) -> Robot {
-        let robot_regex = "^p=(?<p_x>.*),(?<p_y>.*) v=(?<v_x>.*),(?<v_y>.*)$";
-        let re = Regex::new(robot_regex).expect("invalid regex");
+        let re = Regex::new(ROBOT_REGEX).expect("invalid regex");
         let captures = re.captures(line).unwrap();
         Self {
             position: Coord::new(
@@ -49,113 +88,227 @@ impl Robot {
     }
 }
 
-fn get_neighbour_count(x: &Robot, coords: &Vec<Robot>) -> usize {
-    coords
-        .iter()
-        .filter(|c| {
-            let d = &c.position - &x.position;
-            d.x.abs() + d.y.abs() <= 2
+#[cfg(feature = "serde")]
+fn robots_after(input: &str, steps: i32, max_x: usize, max_y: usize) -> Vec<Robot> {
+    let mut robots = Robot::parse_robots(input);
+    for robot in &mut robots {
+        robot.position = robot.get_pos_after(steps, max_x as i32, max_y as i32);
+    }
+    robots
+}
+
+fn render_robot_coords(robot_coords: &[Coord], max_x: usize, max_y: usize) -> String {
+    (0..max_y + 1)
+        .map(|i| {
+            (0..max_x + 1)
+                .map(|j| {
+                    let c = Coord::new(j as i32, i as i32);
+                    match robot_coords.iter().filter(|rb| **rb == c).count() {
+                        0 => '.',
+                        res @ 1..=9 => char::from_digit(res as u32, 10).unwrap(),
+                        _ => '+',
+                    }
+                })
+                .collect::<String>()
+                + "\n"
         })
-        .count()
+        .collect()
 }
 
-fn get_neighbour_score(robot_coords: &Vec<Robot>) -> usize {
-    robot_coords
-        .iter()
-        .fold(0, |acc, c| acc + get_neighbour_count(c, &robot_coords))
+fn visualize_robot_coords(robot_coords: &[Coord], max_x: usize, max_y: usize) {
+    for row in render_robot_coords(robot_coords, max_x, max_y).lines() {
+        println!("{:?}", row);
+    }
 }
 
-fn visualize_robot_coords(robot_coords: &Vec<Coord>, max_x: usize, max_y: usize) {
-    let mut matrix = Vec::new();
-    for _i in 0..max_y + 1 {
-        matrix.push(Vec::new());
-        for j in 0..max_x + 1 {
-            let c = Coord::new(j as i32, i as i32);
-            let res = robot_coords.iter().filter(|rb| **rb == c).count();
-            let robot_vis = char::from_digit(res as u32, 10).unwrap();
-            matrix[i].push(robot_vis);
+fn robot_grid(robots: &[Robot], max_x: usize, max_y: usize) -> BitGrid {
+    let mut grid = BitGrid::new(max_x + 1, max_y + 1);
+    for robot in robots {
+        grid.set_coord(&robot.position, true);
+    }
+    grid
+}
+
+#[derive(Clone)]
+struct Space {
+    robots: Vec<Robot>,
+    max_x: usize,
+    max_y: usize,
+}
+
+impl Space {
+    fn new(input: &str, max_x: usize, max_y: usize) -> Self {
+        Space {
+            robots: Robot::parse_robots(input),
+            max_x,
+            max_y,
         }
     }
-    for i in 0..max_y + 1 {
-        let row = matrix[i]
-            .iter()
-            .map(|c| if *c == '0' { '.' } else { *c })
-            .collect::<String>();
-        println!("{:?}", row);
+
+    fn positions(&self) -> Vec<Coord> {
+        self.robots.iter().map(|r| r.position).collect()
+    }
+
+    // The product of the robot counts in the four quadrants, ignoring the middle lines.
+    fn safety_factor(&self) -> usize {
+        let (mid_x, mid_y) = ((self.max_x / 2) as i32, (self.max_y / 2) as i32);
+        let quadrant = |left: bool, top: bool| {
+            self.robots
+                .iter()
+                .filter(|rc| {
+                    let (x, y) = (rc.position.x, rc.position.y);
+                    (if left { x < mid_x } else { x > mid_x })
+                        && (if top { y < mid_y } else { y > mid_y })
+                })
+                .count()
+        };
+        quadrant(true, true)
+            * quadrant(false, true)
+            * quadrant(true, false)
+            * quadrant(false, false)
     }
 }
 
-fn part_one(input: &str, max_x: usize, max_y: usize) -> i32 {
-    let mut robots = Robot::parse_robots(input);
-    for _i in 0..100 {
-        for robot in &mut robots {
-            robot.move_once(max_x as i32, max_y as i32);
+impl Simulation for Space {
+    fn step(&mut self) {
+        for robot in &mut self.robots {
+            robot.move_once(self.max_x as i32, self.max_y as i32);
         }
     }
 
-    let q1_bots = robots
-        .iter()
-        .filter(|rc| rc.position.x < (max_x / 2) as i32 && rc.position.y < (max_y / 2) as i32)
-        .count();
-    let q2_bots = robots
-        .iter()
-        .filter(|rc| rc.position.x > (max_x / 2) as i32 && rc.position.y < (max_y / 2) as i32)
-        .count();
-    let q3_bots = robots
-        .iter()
-        .filter(|rc| rc.position.x < (max_x / 2) as i32 && rc.position.y > (max_y / 2) as i32)
-        .count();
-    let q4_bots = robots
-        .iter()
-        .filter(|rc| rc.position.x > (max_x / 2) as i32 && rc.position.y > (max_y / 2) as i32)
-        .count();
+    // The robots move forever, though the space repeats every (max_x + 1) * (max_y + 1)
+    // steps at the latest.
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&self.robots)
+    }
+
+    fn render(&self) -> String {
+        render_robot_coords(&self.positions(), self.max_x, self.max_y)
+    }
 
-    (q1_bots * q2_bots * q3_bots * q4_bots) as i32
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let grid = robot_grid(&self.robots, self.max_x, self.max_y);
+        vec![
+            ("safety factor", self.safety_factor().to_string()),
+            (
+                "largest figure",
+                utils::ocr::largest_figure(&grid).to_string(),
+            ),
+        ]
+    }
 }
 
-fn part_two(input: &str, max_x: usize, max_y: usize) -> i32 {
-    let mut robots = Robot::parse_robots(input);
-    let mut max_score = 0;
-    let mut max_score_i = 0;
-    for i in 1..10000 {
-        for robot in &mut robots {
-            robot.move_once(max_x as i32, max_y as i32);
-        }
-        let score = get_neighbour_score(&robots);
-        max_score = max_score.max(score);
-        if score == max_score {
-            max_score_i = i;
-        }
-        if max_score > 3000 {
-            break;
-        }
+fn part_one(input: &str, max_x: usize, max_y: usize) -> i32 {
+    let mut driver = Driver::new(Space::new(input, max_x, max_y));
+    driver.run(100);
+    driver.sim().safety_factor() as i32
+}
+
+// The first step at which a large share of the robots forms one connected figure, and
+// where the robots are at that point.
+fn find_tree(input: &str, max_x: usize, max_y: usize) -> (i32, Vec<Coord>) {
+    let mut driver = Driver::new(Space::new(input, max_x, max_y)).detect_repeats();
+    let min_figure_size = driver.sim().robots.len() / 4;
+    // The initial arrangement does not count.
+    driver.run(1);
+    let stop = driver.run_until(|space| {
+        utils::ocr::contains_figure(&robot_grid(&space.robots, max_x, max_y), min_figure_size)
+    });
+    if stop != Stop::Condition {
+        panic!("No picture found");
     }
-    let mut max_arrangement = Vec::new();
-    for robot in Robot::parse_robots(input) {
-        max_arrangement.push(robot.get_pos_after(max_score_i as i32, max_x as i32, max_y as i32));
+    (driver.steps() as i32, driver.sim().positions())
+}
+
+fn part_two(input: &str, max_x: usize, max_y: usize) -> i32 {
+    let (step, arrangement) = find_tree(input, max_x, max_y);
+    visualize_robot_coords(&arrangement, max_x, max_y);
+    step
+}
+
+fn visualise(input: &str, max_x: usize, max_y: usize) -> String {
+    let (_, arrangement) = find_tree(input, max_x, max_y);
+    let mut svg = Svg::for_grid(max_y + 1, max_x + 1);
+    svg.rect(0.0, 0.0, (max_x + 1) as f64, (max_y + 1) as f64, "black");
+    for position in arrangement {
+        svg.cell(position.y, position.x, "limegreen");
     }
-    visualize_robot_coords(&max_arrangement, max_x, max_y);
-    max_score_i as i32
+    svg.to_string()
+}
+
+// The robots' first steps, then the figure they form.
+#[cfg(feature = "image")]
+fn pictures(runner: &utils::runner::Runner, input: &str, max_x: usize, max_y: usize) {
+    use utils::image::{Animation, Image, Palette, BLACK};
+    let palette = Palette::new([50, 205, 50]).with('.', BLACK);
+    runner.animation("robots", || {
+        let mut driver = Driver::new(Space::new(input, max_x, max_y));
+        let delay = std::time::Duration::from_millis(100);
+        Animation::record(&mut driver, 100, 1, &palette, 4, delay)
+    });
+    runner.image("tree", || {
+        let (_, arrangement) = find_tree(input, max_x, max_y);
+        Image::from_frame(
+            &render_robot_coords(&arrangement, max_x, max_y),
+            &palette,
+            4,
+        )
+    });
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param("max-x", 100usize, "largest x coordinate of the space")
+        .param("max-y", 102usize, "largest y coordinate of the space");
+    #[cfg(feature = "serde")]
+    let runner = runner.param("dump-step", 100i32, "step whose robots --dump-state writes");
+    let runner = runner.from_env();
+    let (max_x, max_y) = (runner.get("max-x"), runner.get("max-y"));
+    runner.validate(|| validate(input, max_x, max_y));
+    #[cfg(feature = "serde")]
+    runner.dump_state("robots", || {
+        robots_after(input, runner.get("dump-step"), max_x, max_y)
+    });
+    runner.visualise(|| visualise(input, max_x, max_y));
+    #[cfg(feature = "image")]
+    pictures(&runner, input, max_x, max_y);
+    #[cfg(feature = "tui")]
+    runner.tui(|| Space::new(input, max_x, max_y));
     println!(
         "{} part one: {}",
         env!("CARGO_PKG_NAME"),
-        part_one(INPUT, 100, 102)
+        runner.solve("one", || part_one(input, max_x, max_y))
     );
     println!(
         "{} part two: {}",
         env!("CARGO_PKG_NAME"),
-        part_two(INPUT, 100, 102)
+        runner.solve("two", || part_two(input, max_x, max_y))
     );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt"), 10, 6).is_empty());
+        assert_eq!(
+            validate("p=0,4 v=3,-3\np=6,3 v=-1,x\np=11,2 v=1,1\n", 10, 6).problems(),
+            [
+                "robot 2: expected a number, found \"x\"",
+                "robot 3: starts at 11,2 outside the space",
+                "robot 4: expected \"p=<x>,<y> v=<dx>,<dy>\", found \"\""
+            ]
+        );
+    }
 
     #[test]
     fn test_robot() {
@@ -167,7 +320,32 @@ mod tests {
     }
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE, 10, 6), 12);
+    fn many_robots_in_one_cell() {
+        let mut robots = vec![Coord::new(1, 2); 12];
+        robots.extend([Coord::new(3, 0); 3]);
+        assert_eq!(
+            render_robot_coords(&robots, 3, 3),
+            "...3\n....\n.+..\n....\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No picture found")]
+    fn gives_up_once_the_space_repeats() {
+        // Robots two apart, all moving alike, never touch.
+        let input = (0..8)
+            .map(|i| format!("p={},{} v=1,1", i % 3 * 2, i / 3 * 2))
+            .collect::<Vec<_>>()
+            .join("\n");
+        find_tree(&input, 5, 5);
+    }
+
+    #[test]
+    fn visualises_figure() {
+        let input = "p=0,0 v=1,0\np=1,0 v=1,0\np=0,1 v=1,0\np=3,4 v=0,1";
+        assert_eq!(find_tree(input, 5, 5).0, 1);
+        let svg = visualise(input, 5, 5);
+        assert_eq!(svg.matches("limegreen").count(), 4);
+        assert!(svg.contains(r#"<rect x="1" y="0" width="1" height="1" fill="limegreen"/>"#));
     }
 }
@@ -1,5 +1,34 @@
 use std::collections::HashSet;
 use utils::coord::Coord;
+use utils::sim::{hash_of, Driver, Simulation};
+use utils::validate::Diagnostics;
+
+// The solver relies on walls all around the warehouse to stop the robot and the boxes.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some((warehouse, moves)) = input.split_once("\n\n") else {
+        diagnostics.report("expected the warehouse and the moves separated by a blank line");
+        return diagnostics;
+    };
+    if let Some(grid) = diagnostics.grid(warehouse.trim(), "#.O@") {
+        diagnostics.exactly_one(&grid, '@', "robot");
+        let (last_row, last_col) = (grid.len() - 1, grid[0].len() - 1);
+        for (i, row) in grid.iter().enumerate() {
+            for (j, c) in row.iter().enumerate() {
+                let on_border = i == 0 || j == 0 || i == last_row || j == last_col;
+                diagnostics.check(!on_border || *c == '#', || {
+                    format!("the wall around the warehouse has a gap at ({}, {})", i, j)
+                });
+            }
+        }
+    }
+    for (n, c) in moves.chars().filter(|c| *c != '\n').enumerate() {
+        diagnostics.check("<>^v".contains(c), || {
+            format!("unknown move {:?} at move {}", c, n)
+        });
+    }
+    diagnostics
+}
 
 fn parse(input: &str, widen: bool) -> (Vec<Vec<char>>, Vec<Coord>, Coord) {
     let (matrix_str, dirs_str) = input.split_once("\n\n").expect("invalid input");
@@ -41,7 +70,7 @@ fn parse(input: &str, widen: bool) -> (Vec<Vec<char>>, Vec<Coord>, Coord) {
     (matrix, dirs, user_coord)
 }
 
-fn get_matrix_score(matrix: Vec<Vec<char>>) -> i32 {
+fn get_matrix_score(matrix: &[Vec<char>]) -> i32 {
     let mut sum = 0;
 
     for (i, row) in matrix.iter().enumerate() {
@@ -57,12 +86,36 @@ fn get_matrix_score(matrix: Vec<Vec<char>>) -> i32 {
     sum as i32
 }
 
-fn move_boxes(matrix: &mut Vec<Vec<char>>, dirs: &Vec<Coord>, mut user_coord: Coord) {
-    for dir in dirs.iter() {
+#[derive(Clone)]
+struct Warehouse {
+    matrix: Vec<Vec<char>>,
+    dirs: Vec<Coord>,
+    user_coord: Coord,
+    next_dir: usize,
+}
+
+impl Warehouse {
+    fn new(input: &str, widen: bool) -> Self {
+        let (matrix, dirs, user_coord) = parse(input, widen);
+        Warehouse {
+            matrix,
+            dirs,
+            user_coord,
+            next_dir: 0,
+        }
+    }
+}
+
+impl Simulation for Warehouse {
+    // One move of the robot, pushing every box in its way unless a wall stops them.
+    fn step(&mut self) {
+        let matrix = &mut self.matrix;
+        let dir = &self.dirs[self.next_dir];
+        self.next_dir += 1;
         let mut clear_coords = HashSet::new();
         let mut coords_that_move = HashSet::new();
         let mut move_possible = true;
-        coords_that_move.insert((user_coord, '.'));
+        coords_that_move.insert((self.user_coord, '.'));
         let mut work_coords = coords_that_move.clone();
         while move_possible && work_coords.len() > 0 {
             let mut more_coords_that_move = HashSet::new();
@@ -111,45 +164,131 @@ fn move_boxes(matrix: &mut Vec<Vec<char>>, dirs: &Vec<Coord>, mut user_coord: Co
             for (coord, new_value) in coords_that_move.iter() {
                 matrix[(coord.x + dir.x) as usize][(coord.y + dir.y) as usize] = *new_value;
             }
-            user_coord += *dir;
+            self.user_coord += *dir;
         }
     }
+
+    fn is_done(&self) -> bool {
+        self.next_dir == self.dirs.len()
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&(&self.matrix, self.user_coord, self.next_dir))
+    }
+
+    fn render(&self) -> String {
+        let mut rows = self.matrix.clone();
+        rows[self.user_coord.x as usize][self.user_coord.y as usize] = '@';
+        rows.iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("moves left", (self.dirs.len() - self.next_dir).to_string()),
+            ("gps", get_matrix_score(&self.matrix).to_string()),
+        ]
+    }
 }
 
 fn part_one(input: &str) -> i32 {
-    let (mut matrix, dirs, user_coord) = parse(input, false);
-
-    move_boxes(&mut matrix, &dirs, user_coord);
-    get_matrix_score(matrix)
+    let mut driver = Driver::new(Warehouse::new(input, false));
+    driver.finish();
+    get_matrix_score(&driver.into_sim().matrix)
 }
 
 fn part_two(input: &str) -> i32 {
-    let (mut matrix, dirs, user_coord) = parse(input, true);
-    move_boxes(&mut matrix, &dirs, user_coord);
-    get_matrix_score(matrix)
+    let mut driver = Driver::new(Warehouse::new(input, true));
+    driver.finish();
+    get_matrix_score(&driver.into_sim().matrix)
+}
+
+// The robot pushing the boxes around the widened warehouse, in about 200 frames.
+#[cfg(feature = "image")]
+fn animation(input: &str) -> utils::image::Animation {
+    use utils::image::{Animation, Palette, BLACK};
+    let palette = Palette::new(BLACK)
+        .with('#', [128, 128, 128])
+        .with('O', [205, 133, 63])
+        .with('[', [205, 133, 63])
+        .with(']', [205, 133, 63])
+        .with('@', [220, 20, 60]);
+    let warehouse = Warehouse::new(input, true);
+    let steps_per_frame = warehouse.dirs.len().div_ceil(200).max(1);
+    let mut driver = Driver::new(warehouse);
+    let delay = std::time::Duration::from_millis(50);
+    Animation::record(&mut driver, usize::MAX, steps_per_frame, &palette, 4, delay)
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param(
+            "wide",
+            false,
+            "play the widened warehouse of part two with --tui",
+        )
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "tui")]
+    runner.tui(|| Warehouse::new(input, runner.get("wide")));
+    #[cfg(feature = "image")]
+    runner.animation("warehouse", || animation(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_SMALL: &str = include_str!("example_small.txt");
-    const EXAMPLE_SMALL_P2: &str = include_str!("example_small_p2.txt");
+    use utils::sim::Stop;
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn steps_and_rewinds() {
+        let warehouse = Warehouse::new(include_str!("example_small.txt"), false);
+        let mut driver = Driver::new(warehouse).with_history();
+        assert_eq!(driver.run(4), Stop::Steps);
+        assert_eq!(driver.sim().render().lines().nth(1), Some("#..@OO.#"));
+        assert_eq!(driver.rewind(3), 3);
+        assert_eq!(driver.sim().render().lines().nth(1), Some("#..O.O.#"));
+        assert_eq!(driver.sim().render().lines().nth(2), Some("##@.O..#"));
+        assert_eq!(driver.finish(), Stop::Done);
+        assert_eq!(driver.steps(), 15);
+        assert_eq!(
+            driver.sim().counters(),
+            [("moves left", "0".to_string()), ("gps", "2028".to_string())]
+        );
+    }
 
+    #[cfg(feature = "image")]
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE_SMALL), 2028);
-        assert_eq!(part_one(EXAMPLE), 10092);
+    fn animates_the_wide_warehouse() {
+        let animation = animation(include_str!("example.txt"));
+        // 700 moves, 4 per frame, plus the initial frame.
+        assert_eq!(animation.len(), 176);
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 9021);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert!(validate(include_str!("example_small.txt")).is_empty());
+        assert_eq!(
+            validate("@###\n#.O#\n####\n\n<^x").problems(),
+            [
+                "the wall around the warehouse has a gap at (0, 0)",
+                "unknown move 'x' at move 2"
+            ]
+        );
     }
 }
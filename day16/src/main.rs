@@ -1,16 +1,18 @@
 use self::Direction::*;
 use petgraph::data::DataMap;
-use petgraph::dot::{Dot};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::{EdgeRef, IntoEdgesDirected, NodeRef};
 use petgraph::{Graph};
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, Write};
-use std::process::Command;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::rc::Rc;
 use std::slice::Iter;
 use utils::coord::Coord;
+use utils::pq::IndexedMinHeap;
+use utils::sim::{hash_of, Simulation};
+use utils::svg::Svg;
+use utils::validate::Diagnostics;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Direction {
@@ -62,20 +64,13 @@ impl Direction {
     }
 }
 
-fn export_to_png(filename: &str, graph: &Graph<(Coord, Direction), i32>) {
-    let dot_data = format!("{:?}", Dot::new(&graph));
-    let mut file = File::create(std::format!("{}.dot", filename)).expect("Error creating DOT file");
-    file.write_all(dot_data.as_bytes())
-        .expect("Error writing to DOT file");
-    Command::new("sh")
-        .arg("-c")
-        .arg(std::format!(
-            "dot -Tpng {}.dot -o {}.png",
-            filename,
-            filename
-        ))
-        .output()
-        .expect("failed to execute process");
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    if let Some(grid) = diagnostics.grid(input, "#.SE") {
+        diagnostics.exactly_one(&grid, 'S', "start");
+        diagnostics.exactly_one(&grid, 'E', "end");
+    }
+    diagnostics
 }
 
 fn parse_graph(input: &str) -> (Graph<(Coord, Direction), i32>, NodeIndex, Vec<NodeIndex>) {
@@ -146,31 +141,19 @@ fn part_one(input: &str) -> i32 {
     min_score
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct WorkNode(petgraph::graph::NodeIndex, i32);
-
-impl PartialOrd for WorkNode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.1.partial_cmp(&self.1)
-    }
-}
-
-impl Ord for WorkNode {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.1.cmp(&self.1)
-    }
-}
-
-fn part_two(input: &str) -> i32 {
+// The lowest score from the start to the end, and every tile on at least one of the
+// best paths.
+fn best_paths(input: &str) -> (i32, HashSet<Coord>) {
     let (graph, sid, eids) = parse_graph(input);
     let mut map = HashMap::new();
     let mut def = HashSet::new();
     def.insert(graph.node_weight(sid).unwrap().0);
     map.insert(sid, (0, def));
-    let mut work_vec: BinaryHeap<WorkNode> = BinaryHeap::new();
-    work_vec.push(WorkNode(sid, 0));
-    while !work_vec.is_empty() {
-        let WorkNode(closest_node, dis) = work_vec.pop().unwrap();
+    let mut queue = IndexedMinHeap::new();
+    queue.push_or_decrease(sid, 0);
+    // The turns and steps all cost something, so every node is popped once, after all of
+    // its best predecessors.
+    while let Some((closest_node, dis)) = queue.pop_min() {
         let edges_from_closest = graph.edges_directed(closest_node, petgraph::Direction::Outgoing);
         for edge in edges_from_closest {
             let next_dis = dis + edge.weight();
@@ -182,49 +165,241 @@ fn part_two(input: &str) -> i32 {
                 prev_parents.clear();
                 prev_parents.insert(graph.node_weight(closest_node).unwrap().0);
                 prev_parents.extend(edge_parents);
-                work_vec.push(WorkNode(target, next_dis));
+                queue.push_or_decrease(target, next_dis);
             } else if (*prev_dis == next_dis) {
                 prev_parents.insert(graph.node_weight(closest_node).unwrap().0);
                 prev_parents.extend(edge_parents);
-                work_vec.push(WorkNode(target, next_dis));
             } else {
                 // noop
             }
         }
     }
 
-    let mut min_score = i32::MAX;
-    let mut min_map = 0;
+    let min_score = eids.iter().map(|eid| map[eid].0).min().unwrap();
+    let mut tiles = HashSet::new();
     for eid in eids {
-        min_score = min_score.min(map[&eid].0);
-        if min_score == map[&eid].0 {
-            min_map = map[&eid].1.len() + 1;
+        let (score, parents) = map.remove(&eid).unwrap();
+        if score == min_score {
+            tiles.insert(graph[eid].0);
+            tiles.extend(parents);
+        }
+    }
+    (min_score, tiles)
+}
+
+fn part_one_heap(input: &str) -> i32 {
+    best_paths(input).0
+}
+
+fn part_two(input: &str) -> i32 {
+    best_paths(input).1.len() as i32
+}
+
+fn visualise(input: &str) -> String {
+    let matrix = utils::matrix::parse_matrix(input);
+    let mut svg = Svg::for_grid(matrix.len(), matrix.first().map_or(0, Vec::len));
+    let mut markers = Vec::new();
+    for (i, row) in (0i32..).zip(matrix.iter()) {
+        for (j, value) in (0i32..).zip(row.iter()) {
+            match value {
+                '#' => {
+                    svg.cell(i, j, "dimgray");
+                }
+                'S' => markers.push((i, j, "green")),
+                'E' => markers.push((i, j, "red")),
+                _ => {}
+            }
         }
     }
-    min_map as i32
+    for tile in best_paths(input).1 {
+        svg.cell(tile.x, tile.y, "gold");
+    }
+    for (i, j, colour) in markers {
+        svg.circle(j as f64 + 0.5, i as f64 + 0.5, 0.35, colour);
+    }
+    svg.to_string()
+}
+
+// Dijkstra from the start, settling one node per step until it reaches an end. Only
+// played with `--tui`.
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+#[derive(Clone)]
+struct Search<'a> {
+    maze: &'a [Vec<char>],
+    graph: &'a Graph<(Coord, Direction), i32>,
+    ends: &'a [NodeIndex],
+    queue: IndexedMinHeap<NodeIndex, i32>,
+    pops: usize,
+    // The pop that settled each node, shared between snapshots like day06's visited
+    // cells: the search settles the same nodes in the same order after a rewind.
+    settled: Rc<RefCell<HashMap<NodeIndex, usize>>>,
+    score: Option<i32>,
+}
+
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+impl<'a> Search<'a> {
+    fn new(
+        maze: &'a [Vec<char>],
+        graph: &'a Graph<(Coord, Direction), i32>,
+        start: NodeIndex,
+        ends: &'a [NodeIndex],
+    ) -> Self {
+        let mut queue = IndexedMinHeap::new();
+        queue.push_or_decrease(start, 0);
+        Search {
+            maze,
+            graph,
+            ends,
+            queue,
+            pops: 0,
+            settled: Rc::new(RefCell::new(HashMap::new())),
+            score: None,
+        }
+    }
+
+    fn is_settled(&self, node: NodeIndex) -> bool {
+        self.settled
+            .borrow()
+            .get(&node)
+            .is_some_and(|pop| *pop <= self.pops)
+    }
+}
+
+impl Simulation for Search<'_> {
+    fn step(&mut self) {
+        let (node, score) = self.queue.pop_min().unwrap();
+        self.pops += 1;
+        self.settled.borrow_mut().entry(node).or_insert(self.pops);
+        if self.ends.contains(&node) {
+            self.score = Some(score);
+            return;
+        }
+        for edge in self.graph.edges(node) {
+            if !self.is_settled(edge.target()) {
+                self.queue
+                    .push_or_decrease(edge.target(), score + edge.weight());
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.score.is_some() || self.queue.is_empty()
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&self.pops)
+    }
+
+    // Tiles with a settled direction are 'o', those only queued '+'.
+    fn render(&self) -> String {
+        let mut tiles = self.maze.to_vec();
+        for node in self.graph.node_indices() {
+            let (c, _) = self.graph[node];
+            let tile = &mut tiles[c.x as usize][c.y as usize];
+            if *tile == '.' && self.is_settled(node) {
+                *tile = 'o';
+            } else if *tile == '.' && self.queue.contains(&node) {
+                *tile = '+';
+            }
+        }
+        tiles
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
+    }
+
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let settled = self
+            .graph
+            .node_indices()
+            .filter(|node| self.is_settled(*node))
+            .count();
+        let score = self
+            .score
+            .map_or("-".to_string(), |score| score.to_string());
+        vec![
+            ("settled", settled.to_string()),
+            ("queued", self.queue.len().to_string()),
+            ("score", score),
+        ]
+    }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "tui")]
+    {
+        let maze = utils::matrix::parse_matrix(input);
+        let (graph, start, ends) = parse_graph(input);
+        runner.tui(|| Search::new(&maze, &graph, start, &ends));
+    }
+    runner.visualise(|| visualise(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner
+            .variants("one")
+            .variant("petgraph", || part_one(input))
+            .variant("heap", || part_one_heap(input))
+            .solve()
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use utils::sim::{Driver, Stop};
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
     const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_2: &str = include_str!("example_2.txt");
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 7036);
-        assert_eq!(part_one(EXAMPLE_2), 11048);
+    fn searches_and_rewinds() {
+        let maze = utils::matrix::parse_matrix(EXAMPLE);
+        let (graph, start, ends) = parse_graph(EXAMPLE);
+        let mut driver = Driver::new(Search::new(&maze, &graph, start, &ends)).with_history();
+        assert_eq!(driver.run(1), Stop::Steps);
+        assert_eq!(
+            driver.sim().render().lines().nth(13),
+            Some("#S+.#.....#...#")
+        );
+        assert_eq!(driver.finish(), Stop::Done);
+        let counters = driver.sim().counters();
+        assert_eq!(counters[2], ("score", "7036".to_string()));
+        let steps = driver.steps();
+        assert_eq!(driver.rewind(steps), steps);
+        assert_eq!(driver.sim().render(), EXAMPLE.to_string() + "\n");
+        assert_eq!(driver.sim().counters()[0], ("settled", "0".to_string()));
+        driver.finish();
+        assert_eq!(driver.sim().counters(), counters);
+    }
+
+    #[test]
+    fn validation() {
+        assert!(validate(EXAMPLE).is_empty());
+        assert!(validate(include_str!("example_2.txt")).is_empty());
+        assert_eq!(
+            validate("#####\n#S.x#\n#####").problems(),
+            [
+                "unexpected 'x' at (1, 3)",
+                "expected one end ('E'), found none"
+            ]
+        );
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 45);
-        assert_eq!(part_two(EXAMPLE_2), 64);
+    fn visualises_best_paths() {
+        let svg = visualise(EXAMPLE);
+        assert_eq!(svg.matches("gold").count(), 45);
+        assert_eq!(svg.matches("<circle").count(), 2);
     }
 }
@@ -1,6 +1,10 @@
 use regex::Regex;
 use std::ops::BitXor;
+use utils::checked::Checked;
+use utils::sim::{hash_of, Driver, Simulation};
+use utils::validate::Diagnostics;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 struct Registers {
     a: u128,
@@ -30,6 +34,59 @@ impl Registers {
     }
 }
 
+// Opcodes whose operand is a combo operand, where 7 is reserved.
+const COMBO_OPCODES: [u128; 5] = [0, 2, 5, 6, 7];
+
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some((regs, code_str)) = input.split_once("\n\n") else {
+        diagnostics.report("expected the registers and the program separated by a blank line");
+        return diagnostics;
+    };
+    let lines = regs.lines().collect::<Vec<_>>();
+    diagnostics.check(lines.len() == 3, || {
+        format!("expected 3 registers, found {} lines", lines.len())
+    });
+    for (line, name) in lines.iter().zip(["A", "B", "C"]) {
+        let value = line.strip_prefix(&format!("Register {}: ", name));
+        diagnostics.check(value.is_some_and(|v| v.parse::<u128>().is_ok()), || {
+            format!("expected \"Register {}: <number>\", found {:?}", name, line)
+        });
+    }
+    let Some(only_code_str) = code_str.strip_prefix("Program: ") else {
+        diagnostics.report(format!(
+            "expected \"Program: <codes>\", found {:?}",
+            code_str
+        ));
+        return diagnostics;
+    };
+    let mut codes = Vec::new();
+    for (position, code) in only_code_str.split(",").enumerate() {
+        match code.parse::<u128>() {
+            Ok(code) if code < 8 => codes.push(code),
+            _ => diagnostics.report(format!(
+                "program value {:?} at position {} is not a 3-bit number",
+                code, position
+            )),
+        }
+    }
+    if !diagnostics.is_empty() {
+        return diagnostics;
+    }
+    diagnostics.check(codes.len() % 2 == 0, || {
+        format!(
+            "the program has an odd length {}, its last opcode has no operand",
+            codes.len()
+        )
+    });
+    for (position, pair) in (0..).step_by(2).zip(codes.chunks_exact(2)) {
+        diagnostics.check(!(COMBO_OPCODES.contains(&pair[0]) && pair[1] == 7), || {
+            format!("reserved combo operand 7 at position {}", position + 1)
+        });
+    }
+    diagnostics
+}
+
 fn get_combo_operand(operand: u128, registers: &Registers) -> u32 {
     match operand {
         0..4 => operand as u32,
@@ -45,7 +102,7 @@ fn get_combo_operand(operand: u128, registers: &Registers) -> u32 {
 fn adv(operand: u128, registers: &mut Registers) {
     let numerator = registers.a;
     let combo_operand_value = get_combo_operand(operand, registers);
-    registers.a = numerator / 2u128.pow(combo_operand_value);
+    registers.a = numerator / Checked(2u128).pow(combo_operand_value).get();
     registers.instruction_pointer += 2;
 }
 
@@ -87,59 +144,91 @@ fn out(operand: u128, registers: &mut Registers) {
 fn bdv(operand: u128, registers: &mut Registers) {
     let numerator = registers.a;
     let combo_operand_value = get_combo_operand(operand, registers);
-    registers.b = numerator / 2u128.pow(combo_operand_value);
+    registers.b = numerator / Checked(2u128).pow(combo_operand_value).get();
     registers.instruction_pointer += 2;
 }
 
 fn cdv(operand: u128, registers: &mut Registers) {
     let numerator = registers.a;
     let combo_operand_value = get_combo_operand(operand, registers);
-    registers.c = numerator / 2u128.pow(combo_operand_value);
+    registers.c = numerator / Checked(2u128).pow(combo_operand_value).get();
     registers.instruction_pointer += 2;
 }
 
-fn calc(mut registers: Registers, codes: &Vec<u128>) -> (String, Vec<u32>) {
-    let mut instruction_pointer = registers.instruction_pointer;
-    while instruction_pointer < codes.len() as u128 {
-        let instruction_opcode = codes[instruction_pointer as usize];
-        let operand = codes[instruction_pointer as usize + 1];
+#[derive(Clone)]
+struct Vm<'a> {
+    registers: Registers,
+    codes: &'a [u128],
+}
+
+impl Simulation for Vm<'_> {
+    // Executes the instruction at the instruction pointer.
+    fn step(&mut self) {
+        let registers = &mut self.registers;
+        let instruction_pointer = registers.instruction_pointer;
+        let instruction_opcode = self.codes[instruction_pointer as usize];
+        let operand = self.codes[instruction_pointer as usize + 1];
         match instruction_opcode {
-            0 => adv(operand, &mut registers),
-            1 => bxl(operand, &mut registers),
-            2 => bst(operand, &mut registers),
-            3 => jnz(operand, &mut registers),
-            4 => bxc(operand, &mut registers),
-            5 => out(operand, &mut registers),
-            6 => bdv(operand, &mut registers),
-            7 => cdv(operand, &mut registers),
+            0 => adv(operand, registers),
+            1 => bxl(operand, registers),
+            2 => bst(operand, registers),
+            3 => jnz(operand, registers),
+            4 => bxc(operand, registers),
+            5 => out(operand, registers),
+            6 => bdv(operand, registers),
+            7 => cdv(operand, registers),
             _ => {
                 panic!("Invalid instruction opcode");
             }
         }
-        instruction_pointer = registers.instruction_pointer;
     }
-    (registers.output, registers.output_vec)
+
+    fn is_done(&self) -> bool {
+        self.registers.instruction_pointer >= self.codes.len() as u128
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&self.registers)
+    }
+
+    fn render(&self) -> String {
+        let registers = &self.registers;
+        format!(
+            "A={} B={} C={} ip={} out={}",
+            registers.a, registers.b, registers.c, registers.instruction_pointer, registers.output
+        )
+    }
 }
 
-fn part_one(input: &str) -> String {
+fn calc(registers: Registers, codes: &[u128]) -> Registers {
+    let mut driver = Driver::new(Vm { registers, codes });
+    driver.finish();
+    driver.into_sim().registers
+}
+
+// The registers and the program.
+fn parse(input: &str) -> (Registers, Vec<u128>) {
     let (regs, code_str) = input.split_once("\n\n").unwrap();
-    let registers = Registers::new(regs);
     let (_, only_code_str) = code_str.split_once(" ").unwrap();
     let codes = only_code_str
         .split(",")
         .map(|a| a.parse::<u128>().unwrap())
         .collect::<Vec<u128>>();
-    calc(registers, &codes).0
+    (Registers::new(regs), codes)
+}
+
+// The registers once the program halted.
+fn run(input: &str) -> Registers {
+    let (registers, codes) = parse(input);
+    calc(registers, &codes)
+}
+
+fn part_one(input: &str) -> String {
+    run(input).output
 }
 
 fn part_two(input: &str) -> u128 {
-    let (regs, code_str) = input.split_once("\n\n").unwrap();
-    let (_, only_code_str) = code_str.split_once(" ").unwrap();
-    let codes = only_code_str
-        .split(",")
-        .map(|a| a.parse::<u128>().unwrap())
-        .collect::<Vec<u128>>();
-    let mut registers = Registers::new(regs);
+    let (mut registers, codes) = parse(input);
 
     let mut valid = Vec::new();
     valid.push(0);
@@ -148,9 +237,9 @@ fn part_two(input: &str) -> u128 {
         valid = Vec::new();
         for num in old_valid {
             for offset in 0..8u128 {
-                let new_num = 8 * num + offset;
+                let new_num = (Checked(8) * num + offset).get();
                 registers.a = new_num as u128;
-                let res = calc(registers.clone(), &codes).1;
+                let res = calc(registers.clone(), &codes).output_vec;
                 let a = codes.iter().rev().take(res.len()).rev().collect::<Vec<_>>();
                 if a.iter().zip(res).all(|(a, b)| **a == b as u128) {
                     valid.push(new_num);
@@ -159,28 +248,108 @@ fn part_two(input: &str) -> u128 {
         }
     }
 
-    *valid.iter().min().unwrap()
+    // Short outputs match the end of the program too, keep only full copies of it.
+    valid
+        .into_iter()
+        .filter(|a| {
+            registers.a = *a;
+            calc(registers.clone(), &codes).output_vec.len() == codes.len()
+        })
+        .min()
+        .expect("No register A makes the program output itself")
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    #[cfg(feature = "serde")]
+    runner.dump_state("registers", || run(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-    const EXAMPLE_2: &str = include_str!("example_2.txt");
+    use utils::cycle::Cycle;
+    use utils::sim::Stop;
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), "4,6,3,5,6,3,5,2,1,0");
+    fn copies_the_whole_program() {
+        let input = include_str!("example_2.txt");
+        let (mut registers, codes) = parse(input);
+        // A = 0 prints a lone 0, which matches the end of the program without copying it.
+        registers.a = 0;
+        assert_eq!(calc(registers.clone(), &codes).output_vec, [0]);
+        registers.a = part_two(input);
+        assert_eq!(
+            calc(registers, &codes).output_vec,
+            codes.iter().map(|code| *code as _).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn steps_and_detects_loops() {
+        let registers = Registers::new("Register A: 1\nRegister B: 0\nRegister C: 9");
+        let mut driver = Driver::new(Vm {
+            registers: registers.clone(),
+            codes: &[2, 6],
+        });
+        assert_eq!(driver.finish(), Stop::Done);
+        assert_eq!(driver.sim().render(), "A=1 B=1 C=9 ip=2 out=");
+
+        let mut driver = Driver::new(Vm {
+            registers,
+            codes: &[3, 0],
+        })
+        .detect_repeats();
+        assert_eq!(
+            driver.finish(),
+            Stop::Repeat(Cycle {
+                start: 0,
+                length: 1
+            })
+        );
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE_2), 117440);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert!(validate(include_str!("example_2.txt")).is_empty());
+        let registers = "Register A: 10\nRegister B: 0\nRegister C: 0\n\n";
+        assert_eq!(
+            validate(&format!("{}Program: 0,1,5", registers)).problems(),
+            ["the program has an odd length 3, its last opcode has no operand"]
+        );
+        assert_eq!(
+            validate(&format!("{}Program: 0,7,9,1,5,4\n", registers)).problems(),
+            [
+                "program value \"9\" at position 2 is not a 3-bit number",
+                "program value \"4\\n\" at position 5 is not a 3-bit number"
+            ]
+        );
+        assert_eq!(
+            validate(&format!("{}Program: 0,7,1,7", registers)).problems(),
+            ["reserved combo operand 7 at position 1"]
+        );
+        assert_eq!(
+            validate("Register A: x\nRegister B: 0\n\nProgram: 0,1").problems(),
+            [
+                "expected 3 registers, found 2 lines",
+                "expected \"Register A: <number>\", found \"Register A: x\""
+            ]
+        );
     }
 }
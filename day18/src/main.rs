@@ -1,6 +1,31 @@
-use petgraph::visit::NodeRef;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use utils::bitgrid::BitGrid;
 use utils::coord::Coord;
+use utils::sim::{hash_of, Simulation};
+use utils::validate::Diagnostics;
+
+fn validate(input: &str, matrix_size: i32, first_n_bytes: usize) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "byte", |line| {
+        let byte = line.parse::<Coord>()?;
+        if (0..matrix_size).contains(&byte.x) && (0..matrix_size).contains(&byte.y) {
+            Ok(byte)
+        } else {
+            Err(format!(
+                "{},{} is outside the {}x{} memory space",
+                byte.x, byte.y, matrix_size, matrix_size
+            ))
+        }
+    });
+    let bytes = input.lines().count();
+    diagnostics.check(bytes >= first_n_bytes, || {
+        format!(
+            "part one lets {} bytes fall, but there are {}",
+            first_n_bytes, bytes
+        )
+    });
+    diagnostics
+}
 
 fn parse_bytes(input: &str) -> Vec<Coord> {
     input
@@ -14,100 +39,232 @@ fn parse_bytes(input: &str) -> Vec<Coord> {
         .collect()
 }
 
-fn create_graph(
-    matrix_size: i32,
-) -> (
-    petgraph::Graph<Coord, i32>,
-    HashMap<Coord, petgraph::graph::NodeIndex>,
-    petgraph::graph::NodeIndex,
-    petgraph::graph::NodeIndex,
-) {
-    let mut graph = petgraph::Graph::new();
-    let mut nodes = HashMap::new();
-
-    let start_node = graph.add_node(Coord { x: 0, y: 0 });
-    let end_node = graph.add_node(Coord {
-        x: matrix_size - 1,
-        y: matrix_size - 1,
-    });
+fn part_one(input: &str, matrix_size: i32, first_n_bytes: usize) -> i32 {
+    let bytes = parse_bytes(input);
+    let mut memory = Memory::new(&bytes, matrix_size);
+    for _ in 0..first_n_bytes {
+        memory.step();
+    }
+    memory
+        .steps_out()
+        .expect("No path after the first bytes fell") as i32
+}
 
-    nodes.insert(Coord::new(0, 0), start_node);
-    nodes.insert(Coord::new(matrix_size - 1, matrix_size - 1), end_node);
+fn part_two(input: &str, matrix_size: i32) -> Coord {
+    let bytes = parse_bytes(input);
+    let size = matrix_size as usize;
+    let index = |c: Coord| c.x as usize * size + c.y as usize;
+    let removals = bytes.iter().copied().map(index).collect::<Vec<_>>();
+    let blocking_byte = utils::dsu::first_disconnecting_removal(
+        size * size,
+        &removals,
+        |i| {
+            Coord::new((i / size) as i32, (i % size) as i32)
+                .get_neighbours()
+                .into_iter()
+                .filter(|c| (0..matrix_size).contains(&c.x) && (0..matrix_size).contains(&c.y))
+                .map(index)
+        },
+        0,
+        size * size - 1,
+    );
+    let blocking_byte = blocking_byte
+        .expect("No path found, even before any byte falls")
+        .expect("No byte blocks the path");
+    bytes[blocking_byte]
+}
 
-    for i in 0..matrix_size {
-        for j in 0..matrix_size {
-            let coord = Coord::new(i, j);
-            if (i, j) != (0, 0) && (i, j) != (matrix_size - 1, matrix_size - 1) {
-                nodes.insert(coord, graph.add_node(coord));
-            }
+// The bytes falling one per step onto a bit grid, with the shortest way out through
+// what is left.
+#[derive(Clone)]
+struct Memory<'a> {
+    bytes: &'a [Coord],
+    fallen: usize,
+    corrupted: BitGrid,
+}
+
+impl<'a> Memory<'a> {
+    fn new(bytes: &'a [Coord], matrix_size: i32) -> Self {
+        let size = matrix_size as usize;
+        Memory {
+            bytes,
+            fallen: 0,
+            corrupted: BitGrid::new(size, size),
         }
     }
 
-    for (c, node) in &nodes {
-        for neighbour in c.get_neighbours() {
-            if let Some(neighbour_node) = nodes.get(&neighbour) {
-                graph.update_edge(*node, *neighbour_node, 1);
+    fn size(&self) -> i32 {
+        self.corrupted.rows() as i32
+    }
+
+    // Breadth first from the top left to the bottom right corner.
+    fn shortest_path(&self) -> Option<Vec<Coord>> {
+        let start = Coord::new(0, 0);
+        let end = Coord::new(self.size() - 1, self.size() - 1);
+        if self.corrupted.get_coord(&start) {
+            return None;
+        }
+        let mut previous = HashMap::from([(start, start)]);
+        let mut work = VecDeque::from([start]);
+        while let Some(c) = work.pop_front() {
+            if c == end {
+                let mut path = vec![c];
+                while *path.last().unwrap() != start {
+                    path.push(previous[path.last().unwrap()]);
+                }
+                return Some(path);
+            }
+            for neighbour in c.get_neighbours() {
+                if self.corrupted.in_bounds(&neighbour)
+                    && !self.corrupted.get_coord(&neighbour)
+                    && !previous.contains_key(&neighbour)
+                {
+                    previous.insert(neighbour, c);
+                    work.push_back(neighbour);
+                }
             }
         }
+        None
     }
 
-    (graph, nodes, start_node, end_node)
+    // Like `shortest_path`, but only counting the steps, with the reached cells in a
+    // second bit grid.
+    fn steps_out(&self) -> Option<usize> {
+        let start = Coord::new(0, 0);
+        let end = Coord::new(self.size() - 1, self.size() - 1);
+        if self.corrupted.get_coord(&start) {
+            return None;
+        }
+        let mut reached = BitGrid::new(self.corrupted.rows(), self.corrupted.cols());
+        reached.set_coord(&start, true);
+        let mut work = VecDeque::from([(start, 0)]);
+        while let Some((c, steps)) = work.pop_front() {
+            if c == end {
+                return Some(steps);
+            }
+            for neighbour in c.get_neighbours() {
+                if self.corrupted.in_bounds(&neighbour)
+                    && !self.corrupted.get_coord(&neighbour)
+                    && !reached.get_coord(&neighbour)
+                {
+                    reached.set_coord(&neighbour, true);
+                    work.push_back((neighbour, steps + 1));
+                }
+            }
+        }
+        None
+    }
 }
 
-fn part_one(input: &str, matrix_size: i32, first_n_bytes: usize) -> i32 {
-    let bytes = parse_bytes(input);
-    let (mut graph, _nodes, start_node, end_node) = create_graph(matrix_size);
-    graph.retain_nodes(|graph, node_index| {
-        let node_coord = graph
-            .node_weight(node_index)
-            .expect("node should be present");
-        !bytes[..first_n_bytes].contains(node_coord)
-    });
+impl Simulation for Memory<'_> {
+    fn step(&mut self) {
+        self.corrupted.set_coord(&self.bytes[self.fallen], true);
+        self.fallen += 1;
+    }
 
-    let res = petgraph::algo::dijkstra(&graph, start_node, Some(end_node), |_| 1);
+    fn is_done(&self) -> bool {
+        self.fallen == self.bytes.len()
+    }
 
-    *res.get(&end_node).unwrap()
-}
+    fn state_hash(&self) -> u64 {
+        hash_of(&self.fallen)
+    }
 
-fn part_two(input: &str, matrix_size: i32) -> Coord {
-    let bytes = parse_bytes(input);
-    let (mut graph, _nodes, start_node, end_node) = create_graph(matrix_size);
+    fn render(&self) -> String {
+        let path = self.shortest_path().unwrap_or_default();
+        (0..self.size())
+            .map(|y| {
+                (0..self.size())
+                    .map(|x| {
+                        let c = Coord::new(x, y);
+                        if self.corrupted.get_coord(&c) {
+                            '#'
+                        } else if path.contains(&c) {
+                            'O'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
 
-    for byte in bytes {
-        graph.retain_nodes(|graph, node_index| graph.node_weight(node_index) != Some(&byte));
-        if !petgraph::algo::has_path_connecting(&graph, start_node, end_node, None) {
-            return byte;
-        }
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let steps = self
+            .shortest_path()
+            .map_or("blocked".to_string(), |path| (path.len() - 1).to_string());
+        vec![
+            ("fallen", self.fallen.to_string()),
+            ("shortest path", steps),
+        ]
     }
-    panic!("No path found");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE, 7, 12), 22);
+    fn validation() {
+        assert!(validate(include_str!("example.txt"), 7, 12).is_empty());
+        assert_eq!(
+            validate("5,4\n4,2\n4-5\n7,3", 7, 12).problems(),
+            [
+                "byte 3: expected x,y but got \"4-5\"",
+                "byte 4: 7,3 is outside the 7x7 memory space",
+                "part one lets 12 bytes fall, but there are 4"
+            ]
+        );
     }
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE, 7), Coord::new(6, 1));
+    fn bytes_fall() {
+        let bytes = parse_bytes(include_str!("example.txt"));
+        let mut driver = utils::sim::Driver::new(Memory::new(&bytes, 7));
+        driver.run(12);
+        assert_eq!(
+            driver.sim().counters(),
+            [
+                ("fallen", "12".to_string()),
+                ("shortest path", "22".to_string())
+            ]
+        );
+        assert_eq!(driver.sim().render().lines().next(), Some("O..#OOO"));
+        driver.run_until(|memory| memory.shortest_path().is_none());
+        assert_eq!(bytes[driver.steps() - 1], Coord::new(6, 1));
+        assert!(driver.sim().render().lines().all(|row| !row.contains('O')));
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param("matrix-size", 71i32, "width and height of the memory space")
+        .param(
+            "first-n-bytes",
+            1024usize,
+            "number of fallen bytes in part one",
+        )
+        .from_env();
+    let matrix_size = runner.get("matrix-size");
+    runner.validate(|| validate(input, matrix_size, runner.get("first-n-bytes")));
+    #[cfg(feature = "tui")]
+    {
+        let bytes = parse_bytes(input);
+        runner.tui(|| Memory::new(&bytes, matrix_size));
+    }
     println!(
         "{} part one: {}",
         env!("CARGO_PKG_NAME"),
-        part_one(INPUT, 71, 1024)
+        runner.solve("one", || part_one(input, matrix_size, runner.get("first-n-bytes")))
     );
     println!(
-        "{} part two: {:?}",
+        "{} part two: {}",
         env!("CARGO_PKG_NAME"),
-        part_two(INPUT, 71)
+        runner.solve("two", || part_two(input, matrix_size))
     );
 }
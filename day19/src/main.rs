@@ -1,4 +1,30 @@
 use std::collections::HashMap;
+use utils::validate::Diagnostics;
+
+// `parse` splits the designs on every newline, so a trailing one would be an empty
+// design, which counts as possible.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some(sections) = diagnostics.sections(input, &["towels", "designs"]) else {
+        return diagnostics;
+    };
+    let stripes = |pattern: &str| match pattern.chars().find(|c| !"wubrg".contains(*c)) {
+        _ if pattern.is_empty() => Err("has no stripes".to_string()),
+        Some(c) => Err(format!("has an unknown colour {:?}", c)),
+        None => Ok(()),
+    };
+    for (i, towel) in sections[0].split(", ").enumerate() {
+        if let Err(reason) = stripes(towel) {
+            diagnostics.report(format!("towel {}: {}", i + 1, reason));
+        }
+    }
+    for (i, design) in sections[1].split('\n').enumerate() {
+        if let Err(reason) = stripes(design) {
+            diagnostics.report(format!("design {}: {}", i + 1, reason));
+        }
+    }
+    diagnostics
+}
 
 fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
     let (towel_pattern_list_str, design_to_display_str) = input.split_once("\n\n").unwrap();
@@ -28,6 +54,50 @@ fn check_design_combo(design: &str, towels: &Vec<&str>) -> usize {
     *possible_start_positions.get(&design.len()).unwrap_or(&0)
 }
 
+// The towels stored letter by letter, so that all towels matching at a position are
+// found in one walk along the design instead of trying every towel.
+#[derive(Default)]
+struct Trie {
+    children: HashMap<u8, Trie>,
+    is_towel: bool,
+}
+
+impl Trie {
+    fn new(towels: &[&str]) -> Trie {
+        let mut root = Trie::default();
+        for towel in towels {
+            let mut node = &mut root;
+            for letter in towel.bytes() {
+                node = node.children.entry(letter).or_default();
+            }
+            node.is_towel = true;
+        }
+        root
+    }
+
+    fn count_design_combos(&self, design: &str) -> usize {
+        let design = design.as_bytes();
+        let mut ways = vec![0usize; design.len() + 1];
+        ways[0] = 1;
+        for start in 0..design.len() {
+            if ways[start] == 0 {
+                continue;
+            }
+            let mut node = self;
+            for (end, letter) in (start + 1..).zip(&design[start..]) {
+                let Some(child) = node.children.get(letter) else {
+                    break;
+                };
+                if child.is_towel {
+                    ways[end] += ways[start];
+                }
+                node = child;
+            }
+        }
+        ways[design.len()]
+    }
+}
+
 fn part_one(input: &str) -> usize {
     let (towel_patterns, designs) = parse(input);
     designs.iter().fold(0, |acc, design| {
@@ -46,24 +116,64 @@ fn part_two(input: &str) -> usize {
     })
 }
 
+fn part_one_trie(input: &str) -> usize {
+    let (towel_patterns, designs) = parse(input);
+    let trie = Trie::new(&towel_patterns);
+    designs
+        .iter()
+        .filter(|design| trie.count_design_combos(design) > 0)
+        .count()
+}
+
+fn part_two_trie(input: &str) -> usize {
+    let (towel_patterns, designs) = parse(input);
+    let trie = Trie::new(&towel_patterns);
+    designs
+        .iter()
+        .map(|design| trie.count_design_combos(design))
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE), 6);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE), 16);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("r, wx, b\n\nbrwrr\nbggr\n").problems(),
+            [
+                "towel 2: has an unknown colour 'x'",
+                "design 3: has no stripes"
+            ]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part two: {:?}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner
+            .variants("one")
+            .variant("dp", || part_one(input))
+            .variant("trie", || part_one_trie(input))
+            .solve()
+    );
+    println!(
+        "{} part two: {:?}",
+        env!("CARGO_PKG_NAME"),
+        runner
+            .variants("two")
+            .variant("dp", || part_two(input))
+            .variant("trie", || part_two_trie(input))
+            .solve()
+    );
 }
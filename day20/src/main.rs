@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use utils::coord::Coord;
+use utils::validate::Diagnostics;
 
 pub fn get_coord_within_range(coord: &Coord, range: i32) -> Vec<Coord> {
     let mut res = Vec::new();
@@ -16,6 +17,67 @@ pub fn get_coord_within_range(coord: &Coord, range: i32) -> Vec<Coord> {
     res
 }
 
+fn track_neighbours(grid: &[Vec<char>], (i, j): (usize, usize)) -> Vec<(usize, usize)> {
+    [
+        (i.wrapping_sub(1), j),
+        (i + 1, j),
+        (i, j.wrapping_sub(1)),
+        (i, j + 1),
+    ]
+    .into_iter()
+    .filter(|(i, j)| {
+        grid.get(*i)
+            .and_then(|row| row.get(*j))
+            .is_some_and(|c| *c != '#')
+    })
+    .collect()
+}
+
+// `get_race_path` follows the track without looking back, so it has to be a single
+// line from S to E: anything else makes it loop forever or run off the grid.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some(grid) = diagnostics.grid(input, "#.SE") else {
+        return diagnostics;
+    };
+    let start = diagnostics.exactly_one(&grid, 'S', "start");
+    diagnostics.exactly_one(&grid, 'E', "end");
+    let track = (0..grid.len())
+        .flat_map(|i| (0..grid[i].len()).map(move |j| (i, j)))
+        .filter(|(i, j)| grid[*i][*j] != '#')
+        .collect::<Vec<_>>();
+    for (i, j) in &track {
+        let neighbours = track_neighbours(&grid, (*i, *j)).len();
+        let expected = if matches!(grid[*i][*j], 'S' | 'E') {
+            1
+        } else {
+            2
+        };
+        diagnostics.check(neighbours <= expected, || {
+            format!("track branches at ({}, {})", i, j)
+        });
+        diagnostics.check(neighbours >= expected, || {
+            format!("track dead-ends at ({}, {})", i, j)
+        });
+    }
+    if let (Some(start), true) = (start, diagnostics.is_empty()) {
+        let (mut previous, mut current, mut length) = (start, start, 1);
+        while let Some(next) = track_neighbours(&grid, current)
+            .into_iter()
+            .find(|next| *next != previous)
+        {
+            (previous, current, length) = (current, next, length + 1);
+        }
+        diagnostics.check(length == track.len(), || {
+            format!(
+                "{} track cells are not on the way from S to E",
+                track.len() - length
+            )
+        });
+    }
+    diagnostics
+}
+
 fn get_start_coord(matrix: &Vec<Vec<char>>) -> Coord {
     matrix
         .iter()
@@ -89,40 +151,64 @@ fn solver(input: &str, cheat_range: i32, min_cheat_to_consider: i32) -> usize {
     cheats.len()
 }
 
-fn part_one(input: &str, min_cheat_to_consider: i32) -> usize {
-    solver(input, 2, min_cheat_to_consider)
+fn part_one(input: &str, cheat_range: i32, min_cheat_to_consider: i32) -> usize {
+    solver(input, cheat_range, min_cheat_to_consider)
 }
 
-fn part_two(input: &str, min_cheat_to_consider: i32) -> usize {
-    solver(input, 20, min_cheat_to_consider)
+fn part_two(input: &str, cheat_range: i32, min_cheat_to_consider: i32) -> usize {
+    solver(input, cheat_range, min_cheat_to_consider)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("example.txt");
-
-    #[test]
-    fn example_part_one() {
-        assert_eq!(part_one(EXAMPLE, 1), 44);
-    }
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
 
     #[test]
-    fn example_part_two() {
-        assert_eq!(part_two(EXAMPLE, 50), 285);
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("#######\n#S...E#\n###.###\n#######").problems(),
+            ["track branches at (1, 3)", "track dead-ends at (2, 3)"]
+        );
+        assert_eq!(
+            validate("######\n#S..E#\n######\n#....#\n#....#\n######").problems(),
+            [
+                "track branches at (3, 2)",
+                "track branches at (3, 3)",
+                "track branches at (4, 2)",
+                "track branches at (4, 3)"
+            ]
+        );
+        assert_eq!(
+            validate("#####\n#S.E#\n#####\n#..##\n#..##\n#####").problems(),
+            ["4 track cells are not on the way from S to E"]
+        );
     }
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param(
+            "min-cheat",
+            100i32,
+            "minimum picoseconds a cheat has to save",
+        )
+        .param("cheat-range-one", 2i32, "cheat duration in part one")
+        .param("cheat-range-two", 20i32, "cheat duration in part two")
+        .from_env();
+    runner.validate(|| validate(input));
+    let min_cheat_to_consider = runner.get("min-cheat");
     println!(
         "{} part one: {}",
         env!("CARGO_PKG_NAME"),
-        part_one(INPUT, 100)
+        runner.solve("one", || part_one(input, runner.get("cheat-range-one"), min_cheat_to_consider))
     );
     println!(
         "{} part two: {:?}",
         env!("CARGO_PKG_NAME"),
-        part_two(INPUT, 100)
+        runner.solve("two", || part_two(input, runner.get("cheat-range-two"), min_cheat_to_consider))
     );
 }
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::iter;
 use utils::coord::Coord;
 use itertools::Itertools;
+use utils::validate::Diagnostics;
 
 // +---+---+---+
 // | 7 | 8 | 9 |
@@ -233,18 +234,32 @@ fn part_two_line(input: &str, number_of_middle_layers: usize) -> usize {
     min * numerical_part
 }
 
-fn part_one(input: &str) -> usize {
+// Codes are typed on the numeric keypad, and their numeric part is the first three digits.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "code", |code| match code.strip_suffix('A') {
+        Some(digits) if digits.len() == 3 && digits.chars().all(|c| c.is_ascii_digit()) => Ok(()),
+        _ => Err(format!("expected three digits and an A, found {:?}", code)),
+    });
+    diagnostics
+}
+
+fn parse_codes(input: &str) -> Vec<&str> {
+    input.lines().collect()
+}
+
+fn part_one(input: &str, number_of_middle_layers: usize) -> usize {
     let mut sum = 0;
-    for line in input.lines() {
-        sum += part_two_line(line, 2);
+    for line in parse_codes(input) {
+        sum += part_two_line(line, number_of_middle_layers);
     }
     sum
 }
 
-fn part_two(input: &str) -> usize {
+fn part_two(input: &str, number_of_middle_layers: usize) -> usize {
     let mut sum = 0;
-    for line in input.lines() {
-        sum += part_two_line(line, 25);
+    for line in parse_codes(input) {
+        sum += part_two_line(line, number_of_middle_layers);
     }
     sum
 }
@@ -253,6 +268,18 @@ fn part_two(input: &str) -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn validation() {
+        assert!(validate("029A\n980A\n179A\n456A\n379A").is_empty());
+        assert_eq!(
+            validate("029A\n98A\n179B").problems(),
+            [
+                "code 2: expected three digits and an A, found \"98A\"",
+                "code 3: expected three digits and an A, found \"179B\""
+            ]
+        );
+    }
+
     #[test]
     fn calc_example_part_two() {
         assert_eq!(part_two_line("029A", 2,), 68*29);
@@ -275,7 +302,21 @@ mod tests {
 
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param("layers-one", 2usize, "number of directional robots in part one")
+        .param("layers-two", 25usize, "number of directional robots in part two")
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input, runner.get("layers-one")))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input, runner.get("layers-two")))
+    );
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::ops::BitXor;
+use utils::checked::Checked;
+use utils::validate::{number, Diagnostics};
 
 fn mix(secret: i64, value: i64) -> i64 {
     secret.bitxor(value)
@@ -10,7 +12,7 @@ fn prune(secret: i64) -> i64 {
 }
 
 fn evolve_phase_1(secret: i64) -> i64 {
-    let secret_mul_64 = secret * 64i64;
+    let secret_mul_64 = (Checked(secret) * 64i64).get();
     let mixed = mix(secret, secret_mul_64);
     prune(mixed)
 }
@@ -22,7 +24,7 @@ fn evolve_phase_2(secret: i64) -> i64 {
 }
 
 fn evolve_phase_3(secret: i64) -> i64 {
-    let secret_mul_2024 = secret * 2048i64;
+    let secret_mul_2024 = (Checked(secret) * 2048i64).get();
     let mixed = mix(secret, secret_mul_2024);
     prune(mixed)
 }
@@ -50,16 +52,25 @@ fn get_prices(mut secret: i64, n: usize) -> Vec<i64> {
     prices
 }
 
-fn part_one(input: &str) -> i64 {
-    input.lines().map(|line| evolve_n(line.parse::<i64>().unwrap(), 2000)).sum::<i64>()
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "buyer", number::<i64>);
+    diagnostics
 }
 
-fn part_two(input: &str) -> ([i64; 4], i64) {
-    let mut overall_hashmap : HashMap<[i64; 4], i64> = HashMap::new();
-    input.lines().for_each(|line| {
-        let start_price = line.parse::<i64>().unwrap();
+fn parse_secrets(input: &str) -> Vec<i64> {
+    input.lines().map(|line| line.parse::<i64>().unwrap()).collect()
+}
+
+fn part_one(input: &str, iterations: usize) -> i64 {
+    parse_secrets(input).into_iter().map(|secret| evolve_n(secret, iterations)).sum::<Checked<i64>>().get()
+}
+
+fn part_two(input: &str, iterations: usize) -> ([i64; 4], i64) {
+    let mut overall_hashmap : HashMap<[i64; 4], Checked<i64>> = HashMap::new();
+    parse_secrets(input).into_iter().for_each(|start_price| {
         let mut hashmap : HashMap<[i64; 4], i64> = HashMap::new();
-        let prices = get_prices(start_price, 2000);
+        let prices = get_prices(start_price, iterations);
         let price_changes = prices.windows(2).map(|window| window[1] - window[0]).collect::<Vec<_>>();
         for (i, window) in price_changes.windows(4).enumerate() {
             hashmap.entry(<[i64; 4]>::try_from(window).unwrap()).or_insert(*prices.get(i + 4).unwrap());
@@ -69,21 +80,48 @@ fn part_two(input: &str) -> ([i64; 4], i64) {
         }
     });
     let asd = overall_hashmap.iter().max_by(|a, b| a.1.cmp(&b.1)).unwrap();
-    (*asd.0, *asd.1)
+    (*asd.0, asd.1.get())
 }
 
 
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part one: {:?}", env!("CARGO_PKG_NAME"), part_two(INPUT));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .param(
+            "iterations",
+            2000usize,
+            "number of secret numbers each buyer generates",
+        )
+        .from_env();
+    runner.validate(|| validate(input));
+    let iterations = runner.get("iterations");
+    println!(
+        "{} part one: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input, iterations))
+    );
+    println!(
+        "{} part two: {}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input, iterations).1)
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn validation() {
+        assert!(validate("1\n10\n100\n2024").is_empty());
+        assert_eq!(
+            validate("1\n10\n\n2024").problems(),
+            ["buyer 3: expected a number, found \"\""]
+        );
+    }
+
     #[test]
     fn test_mix() {
         assert_eq!(mix(42, 15), 37);
@@ -128,6 +166,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = "1\n2\n3\n2024";
-        assert_eq!(part_two(input), ([-2, 1, -1, 3], 23));
+        assert_eq!(part_two(input, 2000), ([-2, 1, -1, 3], 23));
     }
 }
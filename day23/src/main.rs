@@ -1,6 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use petgraph::visit::{EdgeRef};
 use petgraph::graph::NodeIndex;
+use utils::validate::Diagnostics;
+
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.lines(input, "connection", |line| match line.split_once('-') {
+        Some((a, b)) if !a.is_empty() && !b.is_empty() && a != b => Ok(()),
+        _ => Err(format!(
+            "expected two computers like \"kh-tc\", found {:?}",
+            line
+        )),
+    });
+    diagnostics
+}
 
 fn parse(input: &str) -> (petgraph::graph::UnGraph<&str, i32>, HashMap<&str, NodeIndex>) {
     let mut graph = petgraph::graph::Graph::new_undirected();
@@ -74,9 +87,70 @@ fn start_bron_kerbosch(graph: &petgraph::graph::UnGraph<&str, i32>) -> HashSet<N
     res.iter().max_by(|a,b | a.len().cmp(&b.len())).unwrap().clone()
 }
 
+// Every computer on a circle with the largest LAN party highlighted and labelled.
+fn visualise(graph: &petgraph::graph::UnGraph<&str, i32>) -> String {
+    let clique = start_bron_kerbosch(graph);
+    let count = graph.node_count().max(1) as f64;
+    let position = |node: NodeIndex| {
+        let angle = node.index() as f64 / count * std::f64::consts::TAU;
+        (50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+    };
+    let mut svg = utils::svg::Svg::new(100.0, 100.0);
+    for edge in graph.edge_references() {
+        if !(clique.contains(&edge.source()) && clique.contains(&edge.target())) {
+            svg.line(position(edge.source()), position(edge.target()), "lightgray", 0.05);
+        }
+    }
+    for edge in graph.edge_references() {
+        if clique.contains(&edge.source()) && clique.contains(&edge.target()) {
+            svg.line(position(edge.source()), position(edge.target()), "red", 0.3);
+        }
+    }
+    for node in graph.node_indices() {
+        let (x, y) = position(node);
+        if clique.contains(&node) {
+            svg.circle(x, y, 0.8, "red");
+            svg.text(x + 1.0, y - 1.0, 2.5, graph[node]);
+        } else {
+            svg.circle(x, y, 0.4, "gray");
+        }
+    }
+    svg.to_string()
+}
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {:?}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part one: {:?}", env!("CARGO_PKG_NAME"), part_two(INPUT));
-}
\ No newline at end of file
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    runner.visualise(|| visualise(&parse(input).0));
+    println!(
+        "{} part one: {:?}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {:?}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("kh-tc\nqp kh\nde-de").problems(),
+            [
+                "connection 2: expected two computers like \"kh-tc\", found \"qp kh\"",
+                "connection 3: expected two computers like \"kh-tc\", found \"de-de\""
+            ]
+        );
+    }
+}
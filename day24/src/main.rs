@@ -2,6 +2,7 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::{BitXor};
+use utils::validate::Diagnostics;
 
 trait Gate : Debug {
     fn get_value(&self, gates: &HashMap<String, Box<dyn Gate>>, xs: &Vec<bool>, ys: &Vec<bool>) -> bool;
@@ -149,6 +150,51 @@ impl ComplexGate for AndGate {
     }
 }
 
+// `ConstGate` reads the xs and ys by the number in their name, every gate input has to
+// be set somewhere, and the answer is read from the z wires.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let Some(sections) = diagnostics.sections(input, &["wire values", "gates"]) else {
+        return diagnostics;
+    };
+    let mut wires = diagnostics.lines(sections[0], "wire", |line| match line.split_once(": ") {
+        Some((name, "0" | "1"))
+            if name.starts_with(['x', 'y']) && name[1..].parse::<usize>().is_ok() =>
+        {
+            Ok(name)
+        }
+        _ => Err(format!(
+            "expected \"x<n>: <bit>\" or \"y<n>: <bit>\", found {:?}",
+            line
+        )),
+    });
+    let gates = diagnostics.lines(sections[1], "gate", |line| {
+        match line.split(' ').collect::<Vec<_>>()[..] {
+            [lhs, "AND" | "OR" | "XOR", rhs, "->", output] => Ok((lhs, rhs, output)),
+            _ => Err(format!(
+                "expected \"<wire> <AND|OR|XOR> <wire> -> <wire>\", found {:?}",
+                line
+            )),
+        }
+    });
+    wires.extend(gates.iter().map(|(_, _, output)| *output));
+    let mut unset = Vec::new();
+    for (lhs, rhs, _) in &gates {
+        for input in [lhs, rhs] {
+            if !wires.contains(input) && !unset.contains(input) {
+                unset.push(input);
+            }
+        }
+    }
+    for wire in unset {
+        diagnostics.report(format!("wire {} is never set", wire));
+    }
+    diagnostics.check(wires.iter().any(|wire| wire.starts_with('z')), || {
+        "no gate outputs a z wire".to_string()
+    });
+    diagnostics
+}
+
 fn parse(input: &str) -> (Vec<bool>, Vec<bool>, HashMap<String, Box<dyn Gate>>) {
     let mut gates: HashMap<String, Box<dyn Gate>> = HashMap::new();
     let (const_gates_str, complex_gates_str) = input.split_once("\n\n").unwrap();
@@ -229,7 +275,39 @@ fn part_two(input: &str) -> u64 {
 }
 
 fn main() {
-    const INPUT: &str = include_str!("input.txt");
-    println!("{} part one: {:?}", env!("CARGO_PKG_NAME"), part_one(INPUT));
-    println!("{} part one: {:?}", env!("CARGO_PKG_NAME"), part_two(INPUT));
-}
\ No newline at end of file
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME"))
+        .cache(include_str!("main.rs"), input)
+        .from_env();
+    runner.validate(|| validate(input));
+    println!(
+        "{} part one: {:?}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("one", || part_one(input))
+    );
+    println!(
+        "{} part two: {:?}",
+        env!("CARGO_PKG_NAME"),
+        runner.solve("two", || part_two(input))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("x00: 1\ny00: 2\n\nx00 AND y00 -> z00\nx00 NAND y00 -> z01\nq00 OR y00 -> a00").problems(),
+            [
+                "wire 2: expected \"x<n>: <bit>\" or \"y<n>: <bit>\", found \"y00: 2\"",
+                "gate 2: expected \"<wire> <AND|OR|XOR> <wire> -> <wire>\", found \"x00 NAND y00 -> z01\"",
+                "wire y00 is never set",
+                "wire q00 is never set"
+            ]
+        );
+    }
+}
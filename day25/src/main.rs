@@ -1,3 +1,5 @@
+use utils::validate::Diagnostics;
+
 #[derive(Debug)]
 struct Lock {
     heights: [i32; 5],
@@ -62,9 +64,48 @@ fn parse(input: &str) -> (Vec<Key>, Vec<Lock>) {
     (keys, locks)
 }
 
+// `Lock::new` and `Key::new` count '#' per column into five heights, and `parse` tells
+// them apart by the first character, so every schematic has to be a 7x5 block whose
+// top or bottom row, but not both, is solid.
+fn validate(input: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    for (n, block) in input.split("\n\n").enumerate() {
+        let rows = block.lines().collect::<Vec<_>>();
+        diagnostics.check(rows.len() == 7, || {
+            format!("schematic {} has {} rows, expected 7", n, rows.len())
+        });
+        for (i, row) in rows.iter().enumerate() {
+            diagnostics.check(row.len() == 5, || {
+                format!(
+                    "schematic {} row {} is {} wide, expected 5",
+                    n,
+                    i,
+                    row.len()
+                )
+            });
+            diagnostics.check(row.chars().all(|c| c == '#' || c == '.'), || {
+                format!(
+                    "schematic {} row {} is not made of '#' and '.': {:?}",
+                    n, i, row
+                )
+            });
+        }
+        let solid = [rows.first(), rows.last()].map(|row| row == Some(&"#####"));
+        diagnostics.check(solid[0] != solid[1], || {
+            format!(
+                "schematic {} is neither a lock nor a key, exactly one of its top and bottom rows has to be solid",
+                n
+            )
+        });
+    }
+    diagnostics
+}
 
 fn main() {
-    let (keys, locks) = parse(include_str!("input.txt"));
+    let input = utils::runner::read_input(env!("CARGO_MANIFEST_DIR"));
+    let runner = utils::runner::Runner::new(env!("CARGO_PKG_NAME")).from_env();
+    runner.validate(|| validate(input));
+    let (keys, locks) = runner.time("parse", || parse(input));
     println!("{:?}", keys);
     println!("{:?}", locks);
     let mut sum = 0;
@@ -104,4 +145,17 @@ mod tests {
 #####");
         assert_eq!(key.heights, [5,0,2,1,3]);
     }
+
+    #[test]
+    fn validation() {
+        assert!(validate(include_str!("example.txt")).is_empty());
+        assert_eq!(
+            validate("#####\n.###\n.####\n.####\n.#.#.\n.#...\n.....\n\n#####\n#####").problems(),
+            [
+                "schematic 0 row 1 is 4 wide, expected 5",
+                "schematic 1 has 2 rows, expected 7",
+                "schematic 1 is neither a lock nor a key, exactly one of its top and bottom rows has to be solid"
+            ]
+        );
+    }
 }
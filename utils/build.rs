@@ -0,0 +1,30 @@
+use std::path::{Path, PathBuf};
+
+// Fingerprints utils' own sources for the runner's answer cache, so editing a shared
+// module invalidates the answers of every day built on it.
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    let mut files = Vec::new();
+    collect(Path::new("src"), &mut files);
+    files.sort();
+    let mut hash = 0xcbf29ce484222325u64;
+    for file in files {
+        let name = file.to_string_lossy().into_owned().into_bytes();
+        let content = std::fs::read(&file).expect("utils sources are readable");
+        for byte in name.iter().chain(&content) {
+            hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+    println!("cargo:rustc-env=UTILS_SOURCE_HASH={:016x}", hash);
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).expect("utils/src is readable") {
+        let path = entry.expect("utils/src is readable").path();
+        if path.is_dir() {
+            collect(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// The assignment problem: give every row its own column so that the summed cost is as
+// small as possible. Solved with the Hungarian algorithm in O(rows^2 * cols).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub total: i64,
+    // The column of every row.
+    pub columns: Vec<usize>,
+}
+
+// Needs at least as many columns as rows; `cost(row, col)` may be any value, negative
+// ones included, as long as the sums fit an i64.
+pub fn min_cost_assignment(
+    rows: usize,
+    cols: usize,
+    cost: impl Fn(usize, usize) -> i64,
+) -> Assignment {
+    assert!(rows <= cols, "{} rows cannot get {} columns", rows, cols);
+    const INF: i64 = i64::MAX / 2;
+    // Potentials of the rows and columns, and which row every column is matched to, with
+    // an extra column 0 standing for "unmatched". Rows are 1-based in `matched`.
+    let mut row_potential = vec![0; rows + 1];
+    let mut col_potential = vec![0; cols + 1];
+    let mut matched = vec![0; cols + 1];
+    let mut previous = vec![0; cols + 1];
+    for row in 1..=rows {
+        matched[0] = row;
+        let mut col = 0;
+        let mut slack = vec![INF; cols + 1];
+        let mut visited = vec![false; cols + 1];
+        // Grows a tree of tight edges until it reaches a free column.
+        while matched[col] != 0 {
+            visited[col] = true;
+            let current = matched[col];
+            let mut delta = INF;
+            let mut next = 0;
+            for j in 1..=cols {
+                if visited[j] {
+                    continue;
+                }
+                let reduced = cost(current - 1, j - 1) - row_potential[current] - col_potential[j];
+                if reduced < slack[j] {
+                    slack[j] = reduced;
+                    previous[j] = col;
+                }
+                if slack[j] < delta {
+                    delta = slack[j];
+                    next = j;
+                }
+            }
+            for j in 0..=cols {
+                if visited[j] {
+                    row_potential[matched[j]] += delta;
+                    col_potential[j] -= delta;
+                } else {
+                    slack[j] -= delta;
+                }
+            }
+            col = next;
+        }
+        // Flips the matching along the path back to the new row.
+        while col != 0 {
+            let before = previous[col];
+            matched[col] = matched[before];
+            col = before;
+        }
+    }
+
+    let mut columns = vec![0; rows];
+    for (col, row) in matched.iter().enumerate().skip(1) {
+        if *row != 0 {
+            columns[row - 1] = col - 1;
+        }
+    }
+    let total = columns.iter().enumerate().map(|(r, c)| cost(r, *c)).sum();
+    Assignment { total, columns }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tries every permutation.
+    fn brute_force(costs: &[Vec<i64>]) -> i64 {
+        fn go(costs: &[Vec<i64>], row: usize, used: &mut [bool]) -> i64 {
+            if row == costs.len() {
+                return 0;
+            }
+            let mut best = i64::MAX;
+            for col in 0..used.len() {
+                if !used[col] {
+                    used[col] = true;
+                    best = best.min(costs[row][col] + go(costs, row + 1, used));
+                    used[col] = false;
+                }
+            }
+            best
+        }
+        go(costs, 0, &mut vec![false; costs[0].len()])
+    }
+
+    #[test]
+    fn assigns() {
+        let costs = [vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        let assignment = min_cost_assignment(3, 3, |r, c| costs[r][c]);
+        assert_eq!(
+            assignment,
+            Assignment {
+                total: 5,
+                columns: vec![1, 0, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn rectangular_and_negative() {
+        let costs = [vec![-5, 9, 1, 4], vec![-6, 2, -1, 7]];
+        let assignment = min_cost_assignment(2, 4, |r, c| costs[r][c]);
+        assert_eq!(assignment.total, -6);
+        assert_eq!(assignment.columns, [0, 2]);
+        assert_eq!(min_cost_assignment(0, 3, |_, _| 1).total, 0);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut value = 7u64;
+        for n in 1..=6 {
+            for _ in 0..20 {
+                let costs = (0..n)
+                    .map(|_| {
+                        (0..n)
+                            .map(|_| {
+                                value = value
+                                    .wrapping_mul(6364136223846793005)
+                                    .wrapping_add(1442695040888963407);
+                                (value >> 40) as i64 % 50 - 10
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let assignment = min_cost_assignment(n, n, |r, c| costs[r][c]);
+                assert_eq!(assignment.total, brute_force(&costs));
+                let mut columns = assignment.columns.clone();
+                columns.sort();
+                assert_eq!(columns, (0..n).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "3 rows cannot get 2 columns")]
+    fn needs_enough_columns() {
+        min_cost_assignment(3, 2, |_, _| 0);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::coord::Coord;
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Range};
+
+// Fixed size grid of booleans packed into 64 bit words, each row starting on a new
+// word so row operations never have to shift. Rows are `x` and columns `y`.
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words_per_row = cols.div_ceil(64);
+        BitGrid {
+            rows,
+            cols,
+            words_per_row,
+            words: vec![0; rows * words_per_row],
+        }
+    }
+
+    pub fn from_matrix<T>(matrix: &[Vec<T>], is_set: impl Fn(&T) -> bool) -> Self {
+        let cols = matrix.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid = BitGrid::new(matrix.len(), cols);
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                if is_set(value) {
+                    grid.set(i, j, true);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) is out of bounds",
+            row,
+            col
+        );
+        self.words[row * self.words_per_row + col / 64] >> (col % 64) & 1 == 1
+    }
+
+    // Coordinates outside the grid read as unset.
+    pub fn get_coord(&self, coord: &Coord) -> bool {
+        self.in_bounds(coord) && self.get(coord.x as usize, coord.y as usize)
+    }
+
+    pub fn in_bounds(&self, coord: &Coord) -> bool {
+        coord.x >= 0
+            && coord.y >= 0
+            && (coord.x as usize) < self.rows
+            && (coord.y as usize) < self.cols
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) is out of bounds",
+            row,
+            col
+        );
+        let word = &mut self.words[row * self.words_per_row + col / 64];
+        if value {
+            *word |= 1 << (col % 64);
+        } else {
+            *word &= !(1 << (col % 64));
+        }
+    }
+
+    pub fn set_coord(&mut self, coord: &Coord, value: bool) {
+        self.set(coord.x as usize, coord.y as usize, value);
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        &self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    pub fn fill_row(&mut self, row: usize, value: bool) {
+        let cols = self.cols;
+        let words = &mut self.words[row * self.words_per_row..(row + 1) * self.words_per_row];
+        words.fill(if value { u64::MAX } else { 0 });
+        if value && !cols.is_multiple_of(64) {
+            *words.last_mut().unwrap() = (1 << (cols % 64)) - 1;
+        }
+    }
+
+    pub fn fill_col(&mut self, col: usize, value: bool) {
+        for row in 0..self.rows {
+            self.set(row, col, value);
+        }
+    }
+
+    pub fn row_count(&self, row: usize) -> usize {
+        self.count_range(row, 0..self.cols)
+    }
+
+    pub fn col_count(&self, col: usize) -> usize {
+        (0..self.rows).filter(|row| self.get(*row, col)).count()
+    }
+
+    // Number of set bits of `row` within `cols`; the range is clipped to the grid.
+    pub fn count_range(&self, row: usize, cols: Range<usize>) -> usize {
+        let end = cols.end.min(self.cols);
+        if row >= self.rows || cols.start >= end {
+            return 0;
+        }
+        let words = self.row_words(row);
+        let (first, last) = (cols.start / 64, (end - 1) / 64);
+        (first..=last)
+            .map(|w| {
+                let mut word = words[w];
+                if w == first {
+                    word &= u64::MAX << (cols.start % 64);
+                }
+                if w == last && !end.is_multiple_of(64) {
+                    word &= (1 << (end % 64)) - 1;
+                }
+                word.count_ones() as usize
+            })
+            .sum()
+    }
+
+    // Set cells within the square of the given radius around (row, col), itself excluded.
+    pub fn count_in_square(&self, row: usize, col: usize, radius: usize) -> usize {
+        let cols = col.saturating_sub(radius)..col + radius + 1;
+        let own = self.get(row, col) as usize;
+        (row.saturating_sub(radius)..=row + radius)
+            .map(|r| self.count_range(r, cols.clone()))
+            .sum::<usize>()
+            - own
+    }
+
+    pub fn count_neighbours(&self, row: usize, col: usize) -> usize {
+        self.count_in_square(row, col, 1)
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.rows).flat_map(move |row| {
+            self.row_words(row)
+                .iter()
+                .enumerate()
+                .flat_map(move |(w, word)| {
+                    let mut word = *word;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some(Coord::new(row as i32, (w * 64 + bit) as i32))
+                    })
+                })
+        })
+    }
+
+    fn assert_same_shape(&self, rhs: &BitGrid) {
+        assert_eq!(
+            (self.rows, self.cols),
+            (rhs.rows, rhs.cols),
+            "grids differ in shape"
+        );
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a &= b);
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(picture: &str) -> BitGrid {
+        BitGrid::from_matrix(&crate::matrix::parse_matrix(picture), |c| *c == '#')
+    }
+
+    #[test]
+    fn set_and_get() {
+        let mut g = BitGrid::new(3, 130);
+        g.set(1, 0, true);
+        g.set(1, 64, true);
+        g.set(2, 129, true);
+        assert!(g.get(1, 64));
+        assert!(!g.get(1, 63));
+        assert!(g.get_coord(&Coord::new(2, 129)));
+        assert!(!g.get_coord(&Coord::new(-1, 0)));
+        assert!(!g.get_coord(&Coord::new(3, 0)));
+        assert_eq!(g.count_ones(), 3);
+        g.set(1, 64, false);
+        assert_eq!(g.count_ones(), 2);
+        assert_eq!(
+            g.iter_ones().collect::<Vec<_>>(),
+            vec![Coord::new(1, 0), Coord::new(2, 129)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut g = BitGrid::new(4, 70);
+        g.fill_row(2, true);
+        assert_eq!(g.row_count(2), 70);
+        assert_eq!(g.count_ones(), 70);
+        g.fill_col(65, true);
+        assert_eq!(g.col_count(65), 4);
+        assert_eq!(g.count_ones(), 73);
+        assert_eq!(g.count_range(2, 60..68), 8);
+        assert_eq!(g.count_range(2, 68..200), 2);
+        g.fill_row(2, false);
+        assert_eq!(g.count_ones(), 3);
+    }
+
+    #[test]
+    fn neighbours() {
+        let g = grid("#.#\n.##\n###");
+        assert_eq!(g.count_neighbours(1, 1), 6);
+        assert_eq!(g.count_neighbours(0, 0), 1);
+        assert_eq!(g.count_neighbours(2, 2), 3);
+        assert_eq!(g.count_in_square(0, 0, 5), 6);
+    }
+
+    #[test]
+    fn bit_operations() {
+        let mut a = grid("##.\n.#.");
+        let b = grid(".##\n.#.");
+        let mut c = a.clone();
+        c &= &b;
+        assert_eq!(c, grid(".#.\n.#."));
+        c |= &a;
+        assert_eq!(c, a);
+        a ^= &b;
+        assert_eq!(a, grid("#.#\n..."));
+    }
+}
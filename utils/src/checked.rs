@@ -0,0 +1,221 @@
+use std::cell::Cell;
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+// Integer wrapper for accumulators that may outgrow their type on large inputs.
+// Without the `checked` feature it compiles down to the plain operators, with it
+// every operation is checked and an overflow panics with the day, part and operation.
+
+thread_local! {
+    static CONTEXT: Cell<Option<(&'static str, &'static str)>> = const { Cell::new(None) };
+}
+
+pub fn with_context<R>(day: &'static str, part: &'static str, f: impl FnOnce() -> R) -> R {
+    let previous = CONTEXT.replace(Some((day, part)));
+    let res = f();
+    CONTEXT.set(previous);
+    res
+}
+
+#[cold]
+#[inline(never)]
+fn overflow(lhs: impl Display, op: &str, rhs: impl Display) -> ! {
+    match CONTEXT.get() {
+        Some((day, part)) => panic!("{} part {}: overflow in {} {} {}", day, part, lhs, op, rhs),
+        None => panic!("overflow in {} {} {}", lhs, op, rhs),
+    }
+}
+
+pub trait Integer:
+    Copy + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn pow(self, exp: u32) -> Self;
+    fn try_from_usize(value: usize) -> Option<Self>;
+    fn from_usize(value: usize) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+            fn pow(self, exp: u32) -> Self {
+                <$t>::pow(self, exp)
+            }
+            fn try_from_usize(value: usize) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+            fn from_usize(value: usize) -> Self {
+                value as $t
+            }
+        })*
+    };
+}
+
+impl_integer!(i32, u32, i64, u64, i128, u128, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Checked<T>(pub T);
+
+impl<T: Integer> Checked<T> {
+    pub fn get(self) -> T {
+        self.0
+    }
+
+    #[inline]
+    pub fn pow(self, exp: u32) -> Self {
+        if cfg!(feature = "checked") {
+            Checked(
+                self.0
+                    .checked_pow(exp)
+                    .unwrap_or_else(|| overflow(self.0, "pow", exp)),
+            )
+        } else {
+            Checked(self.0.pow(exp))
+        }
+    }
+
+    #[inline]
+    pub fn from_usize(value: usize) -> Self {
+        if cfg!(feature = "checked") {
+            Checked(
+                T::try_from_usize(value)
+                    .unwrap_or_else(|| overflow(value, "as", std::any::type_name::<T>())),
+            )
+        } else {
+            Checked(T::from_usize(value))
+        }
+    }
+}
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+macro_rules! impl_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $checked_fn:ident, $symbol:literal) => {
+        impl<T: Integer> $op<T> for Checked<T> {
+            type Output = Checked<T>;
+
+            #[inline]
+            fn $op_fn(self, rhs: T) -> Self::Output {
+                if cfg!(feature = "checked") {
+                    Checked(
+                        self.0
+                            .$checked_fn(rhs)
+                            .unwrap_or_else(|| overflow(self.0, $symbol, rhs)),
+                    )
+                } else {
+                    Checked($op::$op_fn(self.0, rhs))
+                }
+            }
+        }
+
+        impl<T: Integer> $op for Checked<T> {
+            type Output = Checked<T>;
+
+            #[inline]
+            fn $op_fn(self, rhs: Checked<T>) -> Self::Output {
+                $op::$op_fn(self, rhs.0)
+            }
+        }
+
+        impl<T: Integer> $assign<T> for Checked<T> {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: T) {
+                *self = $op::$op_fn(*self, rhs);
+            }
+        }
+
+        impl<T: Integer> $assign for Checked<T> {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: Checked<T>) {
+                *self = $op::$op_fn(*self, rhs.0);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign, checked_add, "+");
+impl_op!(Sub, sub, SubAssign, sub_assign, checked_sub, "-");
+impl_op!(Mul, mul, MulAssign, mul_assign, checked_mul, "*");
+
+impl<T: Integer> Sum<T> for Checked<T> {
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Self {
+        iter.fold(Checked(T::ZERO), |acc, x| acc + x)
+    }
+}
+
+impl<T: Integer> Sum for Checked<T> {
+    fn sum<I: Iterator<Item = Checked<T>>>(iter: I) -> Self {
+        iter.fold(Checked(T::ZERO), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut acc = Checked(10u64);
+        acc += 5;
+        acc *= Checked(3);
+        acc -= 1;
+        assert_eq!(acc.get(), 44);
+        assert_eq!(Checked(10u64).pow(3).get(), 1000);
+        assert_eq!(Checked::<i32>::from_usize(42).get(), 42);
+        assert_eq!([1i64, 2, 3].into_iter().sum::<Checked<i64>>().get(), 6);
+    }
+
+    #[test]
+    fn context_is_restored() {
+        with_context("day00", "one", || {
+            with_context("day00", "two", || {
+                assert_eq!(CONTEXT.get(), Some(("day00", "two")))
+            });
+            assert_eq!(CONTEXT.get(), Some(("day00", "one")));
+        });
+        assert_eq!(CONTEXT.get(), None);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "day00 part one: overflow in 4294967295 + 1")]
+    fn overflow_is_reported() {
+        with_context("day00", "one", || Checked(u32::MAX) + 1);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "overflow in 2 pow 128")]
+    fn pow_overflow_is_reported() {
+        Checked(2u128).pow(128);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "overflow in 4294967296 as i32")]
+    fn narrowing_is_reported() {
+        Checked::<i32>::from_usize(1 << 32);
+    }
+}
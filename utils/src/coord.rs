@@ -1,5 +1,8 @@
+use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign, Mul, Sub};
+use std::str::FromStr;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Ord, PartialOrd)]
 pub struct Coord {
     pub x: i32,
@@ -25,6 +28,25 @@ impl Coord {
     }
 }
 
+// Written as "x,y", the way puzzles list coordinates.
+impl Display for Coord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| format!("expected x,y but got {:?}", s))?;
+        let parse = |v: &str| v.trim().parse::<i32>().map_err(|e| e.to_string());
+        Ok(Coord::new(parse(x)?, parse(y)?))
+    }
+}
+
 impl Sub for &Coord {
     type Output = Coord;
 
@@ -55,3 +77,49 @@ impl Mul<i32> for Coord {
         Coord::new(self.x * rhs, self.y * rhs)
     }
 }
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Coord,
+    pub max: Coord,
+}
+
+impl BoundingBox {
+    pub fn new(min: Coord, max: Coord) -> Self {
+        BoundingBox { min, max }
+    }
+
+    pub fn from_coords<'a>(coords: impl IntoIterator<Item = &'a Coord>) -> Option<Self> {
+        let mut coords = coords.into_iter();
+        let first = *coords.next()?;
+        let mut bounding_box = BoundingBox::new(first, first);
+        for coord in coords {
+            bounding_box.extend(*coord);
+        }
+        Some(bounding_box)
+    }
+
+    pub fn extend(&mut self, coord: Coord) {
+        self.min = Coord::new(self.min.x.min(coord.x), self.min.y.min(coord.y));
+        self.max = Coord::new(self.max.x.max(coord.x), self.max.y.max(coord.y));
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        (self.min.x..=self.max.x).contains(&coord.x) && (self.min.y..=self.max.y).contains(&coord.y)
+    }
+
+    // The extents along `x` and `y`, named like `BitGrid`'s for grids whose rows are `x`.
+    pub fn rows(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn cols(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (self.min.x..=self.max.x)
+            .flat_map(|x| (self.min.y..=self.max.y).map(move |y| Coord::new(x, y)))
+    }
+}
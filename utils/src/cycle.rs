@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Cycle detection over the sequence x0, step(x0), step(step(x0)), ... The step
+// returns None once the simulation ends (e.g. the guard walks off the map), in
+// which case there is no cycle. Steps must be deterministic.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // Index of the state equal to the one reached after `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+pub fn floyd<T: Clone + PartialEq>(
+    initial: T,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<Cycle> {
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        let half_step = step(&hare)?;
+        hare = step(&half_step)?;
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+pub fn brent<T: Clone + PartialEq>(
+    initial: T,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<Cycle> {
+    let length = brent_length(&initial, &mut step)?;
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+// Only answers whether the sequence loops, skipping the search for the cycle start.
+pub fn has_cycle<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> Option<T>) -> bool {
+    brent_length(&initial, &mut step).is_some()
+}
+
+fn brent_length<T: Clone + PartialEq>(
+    initial: &T,
+    step: &mut impl FnMut(&T) -> Option<T>,
+) -> Option<usize> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+    Some(length)
+}
+
+// Remembers every state, so it also works for steps that are expensive to repeat.
+pub fn find_cycle<T: Clone + Eq + Hash>(
+    initial: T,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for i in 0.. {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            return Some(Cycle {
+                start,
+                length: i - start,
+            });
+        }
+        state = step(&state)?;
+    }
+    unreachable!()
+}
+
+// The state after `n` steps, skipping whole cycles once one is found.
+pub fn fast_forward<T: Clone + Eq + Hash>(
+    initial: T,
+    n: usize,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<T> {
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    for i in 0..n {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            let cycle = Cycle {
+                start,
+                length: i - start,
+            };
+            return Some(history.swap_remove(cycle.reduce(n)));
+        }
+        history.push(state.clone());
+        state = step(&state)?;
+    }
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 1 2 3 4 5 2 3 4 5 2 ...
+    fn rho(x: &u32) -> Option<u32> {
+        Some(if *x == 5 { 2 } else { x + 1 })
+    }
+
+    fn ends_at_ten(x: &u32) -> Option<u32> {
+        (*x < 10).then_some(x + 1)
+    }
+
+    fn pseudo_random(x: &u64) -> Option<u64> {
+        Some((x * x + 1) % 255)
+    }
+
+    #[test]
+    fn finds_rho_shaped_cycle() {
+        let expected = Some(Cycle {
+            start: 2,
+            length: 4,
+        });
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+        assert_eq!(find_cycle(0, rho), expected);
+        assert!(has_cycle(0, rho));
+    }
+
+    #[test]
+    fn detectors_agree() {
+        for x0 in 0..255 {
+            let expected = find_cycle(x0, pseudo_random);
+            assert!(expected.is_some());
+            assert_eq!(floyd(x0, pseudo_random), expected);
+            assert_eq!(brent(x0, pseudo_random), expected);
+        }
+    }
+
+    #[test]
+    fn terminating_sequence_has_no_cycle() {
+        assert_eq!(floyd(0, ends_at_ten), None);
+        assert_eq!(brent(0, ends_at_ten), None);
+        assert_eq!(find_cycle(0, ends_at_ten), None);
+        assert!(!has_cycle(0, ends_at_ten));
+    }
+
+    #[test]
+    fn fast_forwards() {
+        assert_eq!(fast_forward(0, 0, rho), Some(0));
+        assert_eq!(fast_forward(0, 4, rho), Some(4));
+        assert_eq!(fast_forward(0, 6, rho), Some(2));
+        assert_eq!(fast_forward(0, 1_000_000_001, rho), Some(5));
+        assert_eq!(fast_forward(0, 7, ends_at_ten), Some(7));
+        assert_eq!(fast_forward(0, 11, ends_at_ten), None);
+
+        let mut x = 7;
+        for _ in 0..1000 {
+            x = pseudo_random(&x).unwrap();
+        }
+        assert_eq!(fast_forward(7, 1000, pseudo_random), Some(x));
+    }
+}
@@ -0,0 +1,216 @@
+// Disjoint-set forest over the elements 0..n with path compression and union by rank.
+
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        Dsu {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            (x, self.parent[x]) = (self.parent[x], root);
+        }
+        root
+    }
+
+    // Returns false if the two were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            (a, b) = (b, a);
+        }
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    // Every set with its members in increasing order, ordered by smallest member.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if index_of_root[root] == usize::MAX {
+                index_of_root[root] = components.len();
+                components.push(Vec::with_capacity(self.size[root]));
+            }
+            components[index_of_root[root]].push(x);
+        }
+        components
+    }
+}
+
+// `a` and `b` are apart even with every element present, so no removal disconnects them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeverConnected;
+
+// Elements 0..n are removed in the order of `removals`, `neighbours` lists the elements
+// adjacent to one. Returns the index of the removal that first disconnects `a` from `b`,
+// or None if they stay connected, answered offline by adding the elements back in
+// reverse order.
+pub fn first_disconnecting_removal<I: IntoIterator<Item = usize>>(
+    n: usize,
+    removals: &[usize],
+    neighbours: impl Fn(usize) -> I,
+    a: usize,
+    b: usize,
+) -> Result<Option<usize>, NeverConnected> {
+    let mut removed_at = vec![None; n];
+    for (i, x) in removals.iter().enumerate() {
+        removed_at[*x].get_or_insert(i);
+    }
+
+    let mut dsu = Dsu::new(n);
+    let mut present = removed_at.iter().map(Option::is_none).collect::<Vec<_>>();
+    let add = |dsu: &mut Dsu, present: &[bool], x: usize| {
+        for neighbour in neighbours(x) {
+            if present[neighbour] {
+                dsu.union(x, neighbour);
+            }
+        }
+    };
+    for x in (0..n).filter(|x| present[*x]) {
+        add(&mut dsu, &present, x);
+    }
+    if present[a] && present[b] && dsu.connected(a, b) {
+        return Ok(None);
+    }
+
+    for (i, x) in removals.iter().enumerate().rev() {
+        if removed_at[*x] != Some(i) {
+            continue;
+        }
+        present[*x] = true;
+        add(&mut dsu, &present, *x);
+        if present[a] && present[b] && dsu.connected(a, b) {
+            return Ok(Some(i));
+        }
+    }
+    Err(NeverConnected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unions_and_sizes() {
+        let mut dsu = Dsu::new(6);
+        assert_eq!(dsu.component_count(), 6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.connected(0, 3));
+        assert!(!dsu.connected(0, 4));
+        assert_eq!(dsu.size(2), 4);
+        assert_eq!(dsu.size(5), 1);
+        assert_eq!(dsu.component_count(), 3);
+        assert_eq!(dsu.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn long_chain_is_compressed() {
+        let mut dsu = Dsu::new(1000);
+        for x in 1..1000 {
+            dsu.union(x - 1, x);
+        }
+        let root = dsu.find(0);
+        assert!((0..1000).all(|x| dsu.parent[x] == root));
+        assert_eq!(dsu.size(0), 1000);
+        assert_eq!(dsu.component_count(), 1);
+    }
+
+    // A path 0 - 1 - 2 - 3 - 4 with a bypass 1 - 5 - 3.
+    fn path_neighbours(x: usize) -> Vec<usize> {
+        match x {
+            0 => vec![1],
+            1 => vec![0, 2, 5],
+            2 => vec![1, 3],
+            3 => vec![2, 4, 5],
+            4 => vec![3],
+            5 => vec![1, 3],
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn reverse_connectivity() {
+        assert_eq!(
+            first_disconnecting_removal(6, &[2, 5, 0], path_neighbours, 0, 4),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            first_disconnecting_removal(6, &[5, 5, 2], path_neighbours, 0, 4),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            first_disconnecting_removal(6, &[2], path_neighbours, 0, 4),
+            Ok(None)
+        );
+        assert_eq!(
+            first_disconnecting_removal(6, &[1, 4], path_neighbours, 3, 4),
+            Ok(Some(1))
+        );
+    }
+
+    #[test]
+    fn never_connected() {
+        assert_eq!(
+            first_disconnecting_removal(2, &[], |_| vec![], 0, 1),
+            Err(NeverConnected)
+        );
+        // 0 - 1   2 - 3, whatever is removed.
+        let pairs = |x: usize| vec![x ^ 1];
+        assert_eq!(
+            first_disconnecting_removal(4, &[1, 2], pairs, 0, 3),
+            Err(NeverConnected)
+        );
+    }
+}
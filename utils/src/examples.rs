@@ -0,0 +1,294 @@
+use std::fmt::Write;
+use toml::{Table, Value};
+
+// Generates a day's example tests from its `src/examples.toml`, called from the day's
+// build script. Each `[[example]]` names a file next to the manifest and the expected
+// answers of either part, optionally with the parameters passed after the input:
+//
+//     [[example]]
+//     file = "example.txt"
+//     one = { answer = 22, params = [7, 12] }
+//     two = "6,1"
+//
+//     [variants]
+//     one = ["part_one_heap"]
+//
+//     [input]
+//     one = 6382875730645
+//
+// Answers are compared with the `Display` output of `part_one` and `part_two`, and of
+// every listed variant of the part. The tests land in `$OUT_DIR/examples.rs`, which the
+// day includes in its tests module.
+//
+// `[input]` holds the answers for our own `input.txt`. Inputs are not checked in, so
+// those tests only exist with the day's `real-inputs` feature.
+
+pub fn generate_tests() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").expect("run from a build script");
+    let out_dir = std::env::var("OUT_DIR").expect("run from a build script");
+    let path = format!("{}/src/examples.toml", manifest_dir);
+    println!("cargo:rerun-if-changed={}", path);
+    let manifest =
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("could not read {}: {}", path, e));
+    let tests = render_tests(&manifest).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    std::fs::write(format!("{}/examples.rs", out_dir), tests).expect("could not write the tests");
+}
+
+struct Case {
+    answer: String,
+    params: Vec<String>,
+}
+
+fn literal(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Float(f) => Ok(format!("{:?}", f)),
+        Value::Boolean(b) => Ok(b.to_string()),
+        Value::String(s) => Ok(format!("{:?}", s)),
+        other => Err(format!("unsupported parameter {}", other)),
+    }
+}
+
+fn answer(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::String(s) => Ok(s.clone()),
+        other => Err(format!("answers are integers or strings, not {}", other)),
+    }
+}
+
+// `one = 22` or `one = { answer = 22, params = [7, 12] }`.
+fn case(value: &Value) -> Result<Case, String> {
+    let Value::Table(table) = value else {
+        return Ok(Case {
+            answer: answer(value)?,
+            params: Vec::new(),
+        });
+    };
+    let params = match table.get("params") {
+        None => Vec::new(),
+        Some(Value::Array(params)) => params.iter().map(literal).collect::<Result<_, _>>()?,
+        Some(other) => return Err(format!("params must be an array, not {}", other)),
+    };
+    let answer = answer(table.get("answer").ok_or("missing answer")?)?;
+    Ok(Case { answer, params })
+}
+
+fn test_name(file: &str, part: &str, taken: &[String]) -> String {
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let stem = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let name = format!("{}_part_{}", stem, part);
+    (1..)
+        .map(|n| match n {
+            1 => name.clone(),
+            n => format!("{}_{}", name, n),
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap()
+}
+
+fn input_cases(manifest: &Table) -> Result<Vec<(&'static str, Case)>, String> {
+    let Some(input) = manifest.get("input") else {
+        return Ok(Vec::new());
+    };
+    let input = input.as_table().ok_or("input must be a table")?;
+    ["one", "two"]
+        .into_iter()
+        .filter_map(|part| Some((part, input.get(part)?)))
+        .map(|(part, value)| {
+            let case = case(value).map_err(|e| format!("input part {}: {}", part, e))?;
+            Ok((part, case))
+        })
+        .collect()
+}
+
+// The recorded answers for `input.txt` as (part, answer), for checking a day's output.
+pub fn input_answers(manifest: &str) -> Result<Vec<(String, String)>, String> {
+    let manifest = manifest.parse::<Table>().map_err(|e| e.to_string())?;
+    Ok(input_cases(&manifest)?
+        .into_iter()
+        .map(|(part, case)| (part.to_string(), case.answer))
+        .collect())
+}
+
+pub fn render_tests(manifest: &str) -> Result<String, String> {
+    let manifest = manifest.parse::<Table>().map_err(|e| e.to_string())?;
+    let variants = |part: &str| -> Result<Vec<String>, String> {
+        let Some(names) = manifest.get("variants").and_then(|v| v.get(part)) else {
+            return Ok(Vec::new());
+        };
+        names
+            .as_array()
+            .ok_or(format!("variants of part {} must be an array", part))?
+            .iter()
+            .map(|name| {
+                name.as_str()
+                    .map(str::to_string)
+                    .ok_or(format!("variant {} is not a function name", name))
+            })
+            .collect()
+    };
+    let examples = match manifest.get("example") {
+        Some(Value::Array(examples)) => examples.as_slice(),
+        Some(_) => return Err("example must be an array of tables".to_string()),
+        None => &[],
+    };
+
+    let mut cases = Vec::new();
+    for example in examples {
+        let file = example
+            .get("file")
+            .and_then(Value::as_str)
+            .ok_or("example without a file")?;
+        for part in ["one", "two"] {
+            let Some(value) = example.get(part) else {
+                continue;
+            };
+            let case = case(value).map_err(|e| format!("{} part {}: {}", file, part, e))?;
+            cases.push((file, part, case, false));
+        }
+    }
+    for (part, case) in input_cases(&manifest)? {
+        cases.push(("input.txt", part, case, true));
+    }
+
+    let mut names = Vec::new();
+    let mut code = String::new();
+    for (file, part, case, real_input) in cases {
+        let name = test_name(file, part, &names);
+        if real_input {
+            code += "#[cfg(feature = \"real-inputs\")]\n";
+        }
+        writeln!(code, "#[test]\nfn {}() {{", name).unwrap();
+        writeln!(
+            code,
+            "    let input = include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), {:?}));",
+            format!("/src/{}", file)
+        )
+        .unwrap();
+        let args = std::iter::once("input".to_string())
+            .chain(case.params.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let functions = std::iter::once(format!("part_{}", part)).chain(variants(part)?);
+        for function in functions {
+            writeln!(
+                code,
+                "    assert_eq!({}({}).to_string(), {:?}, \"{}\");",
+                function, args, case.answer, function
+            )
+            .unwrap();
+        }
+        code += "}\n\n";
+        names.push(name);
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_cases() {
+        let tests = render_tests(
+            r#"
+            [[example]]
+            file = "example.txt"
+            one = { answer = 22, params = [7, 12] }
+            two = "6,1"
+
+            [[example]]
+            file = "example.txt"
+            two = { answer = 285, params = [20, 50] }
+
+            [[example]]
+            file = "example-small.txt"
+            one = 2
+
+            [variants]
+            one = ["part_one_heap"]
+
+            [input]
+            one = 1400
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tests,
+            r#"#[test]
+fn example_part_one() {
+    let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/example.txt"));
+    assert_eq!(part_one(input, 7, 12).to_string(), "22", "part_one");
+    assert_eq!(part_one_heap(input, 7, 12).to_string(), "22", "part_one_heap");
+}
+
+#[test]
+fn example_part_two() {
+    let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/example.txt"));
+    assert_eq!(part_two(input).to_string(), "6,1", "part_two");
+}
+
+#[test]
+fn example_part_two_2() {
+    let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/example.txt"));
+    assert_eq!(part_two(input, 20, 50).to_string(), "285", "part_two");
+}
+
+#[test]
+fn example_small_part_one() {
+    let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/example-small.txt"));
+    assert_eq!(part_one(input).to_string(), "2", "part_one");
+    assert_eq!(part_one_heap(input).to_string(), "2", "part_one_heap");
+}
+
+#[cfg(feature = "real-inputs")]
+#[test]
+fn input_part_one() {
+    let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input.txt"));
+    assert_eq!(part_one(input).to_string(), "1400", "part_one");
+    assert_eq!(part_one_heap(input).to_string(), "1400", "part_one_heap");
+}
+
+"#
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_manifests() {
+        assert!(render_tests("[[example]]\none = 1").is_err());
+        assert!(render_tests("[[example]]\nfile = \"a.txt\"\none = [1]").is_err());
+        assert!(render_tests("[[example]]\nfile = \"a.txt\"\none = { params = [1] }").is_err());
+        assert!(
+            render_tests("[variants]\none = \"heap\"\n[[example]]\nfile = \"a.txt\"\none = 1")
+                .is_err()
+        );
+        assert!(render_tests("input = 1").is_err());
+        assert!(render_tests("[input]\none = [1]").is_err());
+        assert_eq!(render_tests("").unwrap(), "");
+    }
+
+    #[test]
+    fn input_answers_of_manifest() {
+        let manifest = r#"
+            [[example]]
+            file = "example.txt"
+            one = 1
+
+            [input]
+            one = { answer = 187738, params = [25] }
+            two = "co,de"
+            "#;
+        assert_eq!(
+            input_answers(manifest).unwrap(),
+            [
+                ("one".to_string(), "187738".to_string()),
+                ("two".to_string(), "co,de".to_string())
+            ]
+        );
+        assert_eq!(input_answers("").unwrap(), []);
+    }
+}
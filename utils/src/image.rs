@@ -0,0 +1,354 @@
+use crate::sim::{Driver, Simulation};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+// Raster images of grids: stills as PPM or PNG, simulations as animated GIFs. A cell is
+// drawn as a `scale` x `scale` square of pixels.
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+// The colour of each character of a rendered frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colours: HashMap<char, Rgb>,
+    default: Rgb,
+}
+
+impl Palette {
+    // Characters without a colour of their own are drawn in `default`.
+    pub fn new(default: Rgb) -> Self {
+        Palette {
+            colours: HashMap::new(),
+            default,
+        }
+    }
+
+    pub fn with(mut self, cell: char, colour: Rgb) -> Self {
+        self.colours.insert(cell, colour);
+        self
+    }
+
+    pub fn colour(&self, cell: char) -> Rgb {
+        self.colours.get(&cell).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_grid<T>(matrix: &[Vec<T>], scale: usize, colour: impl Fn(&T) -> Rgb) -> Self {
+        let cols = matrix.iter().map(Vec::len).max().unwrap_or(0);
+        let mut image = Image::new(cols * scale, matrix.len() * scale, BLACK);
+        for (row, cells) in matrix.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                image.fill_cell(row, col, scale, colour(cell));
+            }
+        }
+        image
+    }
+
+    // A frame as `Simulation::render` draws it, one line per row. Rows shorter than the
+    // longest are padded with the palette's default colour.
+    pub fn from_frame(frame: &str, palette: &Palette, scale: usize) -> Self {
+        let rows = frame
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut image = Image::new(cols * scale, rows.len() * scale, palette.default);
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                image.fill_cell(row, col, scale, palette.colour(*cell));
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        self.pixels[y * self.width + x] = colour;
+    }
+
+    pub fn fill_cell(&mut self, row: usize, col: usize, scale: usize, colour: Rgb) {
+        for y in row * scale..(row + 1) * scale {
+            for x in col * scale..(col + 1) * scale {
+                self.set(x, y, colour);
+            }
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.pixels.concat()
+    }
+
+    // Binary PPM (P6), readable without any decoder.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.rgb_bytes());
+        bytes
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().expect("writing to memory");
+        writer
+            .write_image_data(&self.rgb_bytes())
+            .expect("the data matches the header");
+        writer.finish().expect("writing to memory");
+        bytes
+    }
+
+    // The format follows the extension, `.ppm` or `.png`.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png(),
+            _ => {
+                return Err(std::io::Error::other(format!(
+                    "{}: expected a .ppm or .png file",
+                    path.display()
+                )))
+            }
+        };
+        std::fs::write(path, bytes)
+    }
+}
+
+// Frames of equal size shown one after another, looping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    frames: Vec<Image>,
+    delay: Duration,
+}
+
+impl Animation {
+    pub fn new(delay: Duration) -> Self {
+        Animation {
+            frames: Vec::new(),
+            delay,
+        }
+    }
+
+    // Renders the simulation now and after every `steps_per_frame` steps, for at most
+    // `frames` frames or until it is done.
+    pub fn record<S: Simulation>(
+        driver: &mut Driver<S>,
+        frames: usize,
+        steps_per_frame: usize,
+        palette: &Palette,
+        scale: usize,
+        delay: Duration,
+    ) -> Self {
+        let mut animation = Animation::new(delay);
+        animation.push(Image::from_frame(&driver.sim().render(), palette, scale));
+        while animation.len() < frames && !driver.sim().is_done() {
+            driver.run(steps_per_frame);
+            animation.push(Image::from_frame(&driver.sim().render(), palette, scale));
+        }
+        animation
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        if let Some(first) = self.frames.first() {
+            assert_eq!(
+                (frame.width, frame.height),
+                (first.width, first.height),
+                "frames of an animation have the same size"
+            );
+        }
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // Grids use few colours, so they share one exact palette; beyond 256 colours every
+    // frame is quantised on its own.
+    pub fn to_gif(&self) -> Vec<u8> {
+        let (width, height) = self.frames.first().map_or((0, 0), |f| (f.width, f.height));
+        let (width, height) = (
+            u16::try_from(width).expect("GIFs are at most 65535 pixels wide"),
+            u16::try_from(height).expect("GIFs are at most 65535 pixels high"),
+        );
+        let mut indices = HashMap::new();
+        for pixel in self.frames.iter().flat_map(|f| &f.pixels) {
+            let next = indices.len();
+            indices.entry(*pixel).or_insert(next);
+        }
+        let exact = indices.len() <= 256;
+        let mut palette = vec![BLACK; if exact { indices.len() } else { 0 }];
+        for (colour, index) in indices.iter().filter(|_| exact) {
+            palette[*index] = *colour;
+        }
+
+        let mut bytes = Vec::new();
+        let mut encoder = gif::Encoder::new(&mut bytes, width, height, &palette.concat())
+            .expect("writing to memory");
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .expect("writing to memory");
+        for image in &self.frames {
+            let mut frame = if exact {
+                let pixels = image.pixels.iter().map(|p| indices[p] as u8);
+                gif::Frame::from_indexed_pixels(width, height, pixels.collect::<Vec<_>>(), None)
+            } else {
+                gif::Frame::from_rgb(width, height, &image.rgb_bytes())
+            };
+            frame.delay = (self.delay.as_millis() / 10) as u16;
+            encoder.write_frame(&frame).expect("writing to memory");
+        }
+        encoder.into_inner().expect("writing to memory");
+        bytes
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_gif())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::hash_of;
+
+    const RED: Rgb = [255, 0, 0];
+
+    fn palette() -> Palette {
+        Palette::new(WHITE).with('#', BLACK).with('@', RED)
+    }
+
+    #[test]
+    fn frames_to_pixels() {
+        let image = Image::from_frame("#.\n@\n", &palette(), 2);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.pixel(1, 1), BLACK);
+        assert_eq!(image.pixel(2, 0), WHITE);
+        assert_eq!(image.pixel(0, 3), RED);
+        assert_eq!(image.pixel(3, 3), WHITE);
+
+        let grid = Image::from_grid(&[vec![1, 0], vec![0, 1]], 1, |v| [*v * 200; 3]);
+        assert_eq!(grid.pixel(0, 0), [200; 3]);
+        assert_eq!(grid.pixel(1, 0), BLACK);
+    }
+
+    #[test]
+    fn ppm() {
+        let image = Image::from_frame("#@", &palette(), 1);
+        assert_eq!(image.to_ppm(), b"P6\n2 1\n255\n\x00\x00\x00\xff\x00\x00");
+    }
+
+    #[test]
+    fn png() {
+        let image = Image::from_frame("#@.\n...", &palette(), 3);
+        let bytes = image.to_png();
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (9, 6));
+        assert_eq!(data[..info.buffer_size()], image.rgb_bytes());
+    }
+
+    #[test]
+    fn saves_by_extension() {
+        let image = Image::new(1, 1, RED);
+        let path = std::env::temp_dir().join("utils-image-test.ppm");
+        image.save(&path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), image.to_ppm());
+        std::fs::remove_file(path).unwrap();
+        assert!(image.save(Path::new("image.jpg")).is_err());
+    }
+
+    // A dot falling down a column, done at the bottom.
+    struct Faller {
+        at: usize,
+        height: usize,
+    }
+
+    impl Simulation for Faller {
+        fn step(&mut self) {
+            self.at += 1;
+        }
+
+        fn is_done(&self) -> bool {
+            self.at + 1 == self.height
+        }
+
+        fn state_hash(&self) -> u64 {
+            hash_of(&self.at)
+        }
+
+        fn render(&self) -> String {
+            (0..self.height)
+                .map(|i| if i == self.at { "@\n" } else { ".\n" })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn gif_animation() {
+        let mut driver = Driver::new(Faller { at: 0, height: 5 });
+        let delay = Duration::from_millis(100);
+        let animation = Animation::record(&mut driver, 10, 2, &palette(), 1, delay);
+        assert_eq!(animation.len(), 3);
+        assert_eq!(driver.steps(), 4);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options
+            .read_info(std::io::Cursor::new(animation.to_gif()))
+            .unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (1, 5));
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 10);
+            let red = frame.buffer.chunks(4).position(|p| p[..3] == RED);
+            frames.push(red);
+        }
+        assert_eq!(frames, [Some(0), Some(2), Some(4)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "frames of an animation have the same size")]
+    fn frames_have_one_size() {
+        let mut animation = Animation::new(Duration::ZERO);
+        animation.push(Image::new(1, 1, RED));
+        animation.push(Image::new(2, 1, RED));
+    }
+}
@@ -1,2 +1,22 @@
+pub mod assignment;
+pub mod bitgrid;
+pub mod checked;
 pub mod coord;
+pub mod cycle;
+pub mod dsu;
+#[cfg(feature = "examples")]
+pub mod examples;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod math;
 pub mod matrix;
+pub mod ocr;
+pub mod pq;
+pub mod region;
+pub mod runner;
+pub mod sim;
+pub mod sparse_grid;
+pub mod svg;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod validate;
@@ -0,0 +1,302 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: i128, b: i128) -> i128 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+// Returns (g, x, y) with a * x + b * y == g == gcd(a, b).
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+// Combines congruences t = residue (mod modulus) into a single one, returned as
+// (residue, modulus). Moduli do not have to be coprime; None if they contradict.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut res = (0, 1);
+    for &(residue, modulus) in congruences {
+        let (r1, m1) = res;
+        let (g, p, _) = extended_gcd(m1, modulus);
+        let diff = residue - r1;
+        if diff % g != 0 {
+            return None;
+        }
+        let m = m1 / g * modulus;
+        let k = (diff / g % (modulus / g)) * p % (modulus / g);
+        res = ((r1 + m1 * k).rem_euclid(m), m);
+    }
+    Some(res)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert_ne!(denominator, 0, "zero denominator");
+        let g = gcd(numerator, denominator).max(1) * denominator.signum();
+        Rational {
+            numerator: numerator / g,
+            denominator: denominator / g,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::new(value, 1)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let den = lcm(self.denominator, rhs.denominator);
+        Rational::new(
+            self.numerator * (den / self.denominator) + rhs.numerator * (den / rhs.denominator),
+            den,
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational::new(-self.numerator, self.denominator)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let a = gcd(self.numerator, rhs.denominator).max(1);
+        let b = gcd(rhs.numerator, self.denominator).max(1);
+        Rational::new(
+            (self.numerator / a) * (rhs.numerator / b),
+            (self.denominator / b) * (rhs.denominator / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.is_zero(), "division by zero");
+        self * Rational::new(rhs.denominator, rhs.numerator)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+// Cramer's rule; None when the system has no unique solution.
+pub fn solve_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<[Rational; 2]> {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    if det == 0 {
+        return None;
+    }
+    Some([
+        Rational::new(b[0] * a[1][1] - a[0][1] * b[1], det),
+        Rational::new(a[0][0] * b[1] - b[0] * a[1][0], det),
+    ])
+}
+
+// Gaussian elimination over the rationals; None when the system has no unique solution.
+pub fn solve_linear(a: &[Vec<i128>], b: &[i128]) -> Option<Vec<Rational>> {
+    let n = b.len();
+    assert_eq!(a.len(), n, "expected {} equations", n);
+    assert!(a.iter().all(|row| row.len() == n), "matrix is not square");
+
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, b)| row.iter().chain([b]).map(|v| Rational::from(*v)).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|r| !rows[*r][col].is_zero())?;
+        rows.swap(col, pivot);
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == col || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+    }
+    Some((0..n).map(|i| rows[i][n] / rows[i][i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(101, 103), 10403);
+        assert_eq!(lcm(0, 5), 0);
+    }
+
+    #[test]
+    fn extended_euclid() {
+        for (a, b) in [(240, 46), (101, 103), (-35, 15), (0, 9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn modular_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(101, 103), Some(51));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        let (t, m) = crt(&[(12, 101), (40, 103)]).unwrap();
+        assert_eq!((t % 101, t % 103, m), (12, 40, 10403));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn rationals() {
+        let half = Rational::new(2, 4);
+        assert_eq!(half, Rational::new(-1, -2));
+        assert_eq!(half + Rational::new(1, 3), Rational::new(5, 6));
+        assert_eq!(half - Rational::from(1), Rational::new(-1, 2));
+        assert_eq!(half * Rational::new(4, 3), Rational::new(2, 3));
+        assert_eq!(half / Rational::new(1, 4), Rational::from(2));
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+        assert!(Rational::new(1, 3) < half);
+        assert_eq!(Rational::new(3, -6).to_string(), "-1/2");
+    }
+
+    #[test]
+    fn day13_claw_machines() {
+        assert_eq!(
+            solve_2x2([[94, 22], [34, 67]], [8400, 5400]),
+            Some([Rational::from(80), Rational::from(40)])
+        );
+        let [a, b] = solve_2x2([[26, 67], [66, 21]], [12748, 12176]).unwrap();
+        assert!(a.to_integer().is_none() || b.to_integer().is_none());
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), None);
+    }
+
+    #[test]
+    fn linear_systems() {
+        let a = vec![vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(
+            solve_linear(&a, &[8, -11, -3]),
+            Some(vec![
+                Rational::from(2),
+                Rational::from(3),
+                Rational::from(-1)
+            ])
+        );
+        let a = vec![vec![0, 2], vec![3, 0]];
+        assert_eq!(
+            solve_linear(&a, &[1, 1]),
+            Some(vec![Rational::new(1, 3), Rational::new(1, 2)])
+        );
+        let singular = vec![vec![1, 2], vec![2, 4]];
+        assert_eq!(solve_linear(&singular, &[3, 6]), None);
+        assert_eq!(
+            solve_linear(&[vec![94, 22], vec![34, 67]], &[8400, 5400]),
+            solve_2x2([[94, 22], [34, 67]], [8400, 5400]).map(Vec::from)
+        );
+    }
+}
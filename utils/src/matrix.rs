@@ -1,3 +1,7 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Index;
+
 pub fn parse_matrix(input: &str) -> Vec<Vec<char>> {
     input.lines().map(|line| line.chars().collect()).collect()
 }
@@ -14,3 +18,279 @@ pub fn transpose_matrix<T>(original: Vec<Vec<T>>) -> Vec<Vec<T>> {
 
     transposed
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} columns, expected {}",
+                row, found, expected
+            ),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+// Checks that every row has the same length and returns (rows, cols).
+pub fn validate_matrix<T>(matrix: &[Vec<T>]) -> Result<(usize, usize), MatrixError> {
+    let cols = matrix.first().map_or(0, Vec::len);
+    match matrix.iter().position(|row| row.len() != cols) {
+        Some(row) => Err(MatrixError::RaggedRow {
+            row,
+            expected: cols,
+            found: matrix[row].len(),
+        }),
+        None => Ok((matrix.len(), cols)),
+    }
+}
+
+pub fn try_parse_matrix(input: &str) -> Result<Vec<Vec<char>>, MatrixError> {
+    let matrix = parse_matrix(input);
+    validate_matrix(&matrix)?;
+    Ok(matrix)
+}
+
+// Clockwise.
+pub fn rotate_90<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    let cols = matrix.first().map_or(0, Vec::len);
+    (0..cols)
+        .map(|j| matrix.iter().rev().map(|row| row[j].clone()).collect())
+        .collect()
+}
+
+pub fn rotate_180<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    matrix
+        .iter()
+        .rev()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+pub fn rotate_270<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    let cols = matrix.first().map_or(0, Vec::len);
+    (0..cols)
+        .rev()
+        .map(|j| matrix.iter().map(|row| row[j].clone()).collect())
+        .collect()
+}
+
+// Mirrors left and right.
+pub fn flip_horizontal<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    matrix
+        .iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+// Mirrors top and bottom.
+pub fn flip_vertical<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    matrix.iter().rev().cloned().collect()
+}
+
+// Lines running down and to the right, from the bottom left corner to the top right one.
+pub fn diagonals<T: Clone>(matrix: &[Vec<T>]) -> impl Iterator<Item = Vec<T>> + '_ {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, Vec::len);
+    (0..rows + cols).skip(1).map(move |d| {
+        let (i, j) = if d <= rows {
+            (rows - d, 0)
+        } else {
+            (0, d - rows)
+        };
+        (0..(rows - i).min(cols - j))
+            .map(|k| matrix[i + k][j + k].clone())
+            .collect()
+    })
+}
+
+// Lines running down and to the left, from the top left corner to the bottom right one.
+pub fn anti_diagonals<T: Clone>(matrix: &[Vec<T>]) -> impl Iterator<Item = Vec<T>> + '_ {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, Vec::len);
+    (0..rows + cols).skip(1).map(move |d| {
+        let (i, j) = if d <= cols {
+            (0, d - 1)
+        } else {
+            (d - cols, cols - 1)
+        };
+        (0..(rows - i).min(j + 1))
+            .map(|k| matrix[i + k][j - k].clone())
+            .collect()
+    })
+}
+
+// Borrowed rectangular window into a matrix, indexed relative to its top left corner.
+#[derive(Debug, Clone, Copy)]
+pub struct SubMatrix<'a, T> {
+    matrix: &'a [Vec<T>],
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+}
+
+impl<'a, T> SubMatrix<'a, T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // Position of the top left corner in the original matrix.
+    pub fn origin(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Option<&'a T> {
+        if i < self.rows && j < self.cols {
+            Some(&self.matrix[self.row + i][self.col + j])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, i: usize) -> &'a [T] {
+        assert!(i < self.rows, "row {} is out of bounds", i);
+        &self.matrix[self.row + i][self.col..self.col + self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.rows).map(|i| self.row(i))
+    }
+
+    pub fn to_matrix(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.iter_rows().map(|row| row.to_vec()).collect()
+    }
+}
+
+impl<T> Index<(usize, usize)> for SubMatrix<'_, T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        self.get(i, j)
+            .unwrap_or_else(|| panic!("({}, {}) is out of bounds", i, j))
+    }
+}
+
+pub fn submatrix<T>(
+    matrix: &[Vec<T>],
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+) -> Option<SubMatrix<'_, T>> {
+    let fits = row + rows <= matrix.len()
+        && matrix[row..row + rows]
+            .iter()
+            .all(|r| col + cols <= r.len());
+    fits.then_some(SubMatrix {
+        matrix,
+        row,
+        col,
+        rows,
+        cols,
+    })
+}
+
+// Every window of the given size, row by row.
+pub fn windows<T>(
+    matrix: &[Vec<T>],
+    rows: usize,
+    cols: usize,
+) -> impl Iterator<Item = SubMatrix<'_, T>> {
+    let width = matrix.first().map_or(0, Vec::len);
+    (0..(matrix.len() + 1).saturating_sub(rows)).flat_map(move |i| {
+        (0..(width + 1).saturating_sub(cols))
+            .filter_map(move |j| submatrix(matrix, i, j, rows, cols))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers() -> Vec<Vec<u32>> {
+        vec![vec![1, 2, 3], vec![4, 5, 6]]
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let m = numbers();
+        assert_eq!(rotate_90(&m), vec![vec![4, 1], vec![5, 2], vec![6, 3]]);
+        assert_eq!(rotate_180(&m), vec![vec![6, 5, 4], vec![3, 2, 1]]);
+        assert_eq!(rotate_270(&m), vec![vec![3, 6], vec![2, 5], vec![1, 4]]);
+        assert_eq!(rotate_90(&rotate_90(&m)), rotate_180(&m));
+        assert_eq!(rotate_90(&rotate_270(&m)), m);
+        assert_eq!(flip_horizontal(&m), vec![vec![3, 2, 1], vec![6, 5, 4]]);
+        assert_eq!(flip_vertical(&m), vec![vec![4, 5, 6], vec![1, 2, 3]]);
+        assert_eq!(transpose_matrix(m.clone()), flip_horizontal(&rotate_90(&m)));
+    }
+
+    #[test]
+    fn diagonal_lines() {
+        let m = numbers();
+        assert_eq!(
+            diagonals(&m).collect::<Vec<_>>(),
+            vec![vec![4], vec![1, 5], vec![2, 6], vec![3]]
+        );
+        assert_eq!(
+            anti_diagonals(&m).collect::<Vec<_>>(),
+            vec![vec![1], vec![2, 4], vec![3, 5], vec![6]]
+        );
+        assert_eq!(diagonals::<u32>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn submatrix_views() {
+        let m = parse_matrix("abcd\nefgh\nijkl");
+        let view = submatrix(&m, 1, 1, 2, 3).unwrap();
+        assert_eq!(view[(0, 0)], 'f');
+        assert_eq!(view.row(1), &['j', 'k', 'l']);
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.to_matrix(), parse_matrix("fgh\njkl"));
+        assert!(submatrix(&m, 1, 2, 2, 3).is_none());
+
+        let origins = windows(&m, 2, 3).map(|w| w.origin()).collect::<Vec<_>>();
+        assert_eq!(origins, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(windows(&m, 4, 1).count(), 0);
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert_eq!(try_parse_matrix("ab\ncd"), Ok(parse_matrix("ab\ncd")));
+        assert_eq!(
+            try_parse_matrix("ab\nc\nde"),
+            Err(MatrixError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            validate_matrix(&parse_matrix("abc\nabcd"))
+                .unwrap_err()
+                .to_string(),
+            "row 1 has 4 columns, expected 3"
+        );
+    }
+}
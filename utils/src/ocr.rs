@@ -0,0 +1,259 @@
+use crate::bitgrid::BitGrid;
+
+// Reads the block letters some puzzles draw instead of printing an answer. Set
+// cells are ink; letters are separated by at least one blank column.
+
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+// Glyphs are compared column by column, each column packed into the low bits of a u16.
+type Glyph = Vec<u16>;
+
+fn glyph_from_picture(picture: &str) -> Glyph {
+    let matrix = crate::matrix::parse_matrix(picture);
+    trim_blank_columns(
+        (0..matrix[0].len())
+            .map(|j| column_bits(matrix.len(), |i| matrix[i][j] == '#'))
+            .collect(),
+    )
+}
+
+fn column_bits(rows: usize, is_set: impl Fn(usize) -> bool) -> u16 {
+    (0..rows).fold(0, |bits, i| bits | (is_set(i) as u16) << i)
+}
+
+fn trim_blank_columns(mut glyph: Glyph) -> Glyph {
+    while glyph.last() == Some(&0) {
+        glyph.pop();
+    }
+    let leading = glyph.iter().take_while(|c| **c == 0).count();
+    glyph.split_off(leading)
+}
+
+fn font_for_height(height: usize) -> Option<&'static [(char, &'static str)]> {
+    match height {
+        6 => Some(FONT_6),
+        10 => Some(FONT_10),
+        _ => None,
+    }
+}
+
+// The text drawn in `grid`, or None if the ink doesn't form a line of known letters.
+pub fn recognize(grid: &BitGrid) -> Option<String> {
+    let ink_rows = (0..grid.rows())
+        .filter(|row| grid.row_count(*row) > 0)
+        .collect::<Vec<_>>();
+    let (top, bottom) = (*ink_rows.first()?, *ink_rows.last()?);
+    let font = font_for_height(bottom - top + 1)?;
+    let glyphs = font
+        .iter()
+        .map(|(letter, picture)| (*letter, glyph_from_picture(picture)))
+        .collect::<Vec<_>>();
+
+    let columns = (0..grid.cols())
+        .map(|col| column_bits(bottom - top + 1, |i| grid.get(top + i, col)))
+        .collect::<Vec<_>>();
+    columns
+        .split(|column| *column == 0)
+        .filter(|glyph| !glyph.is_empty())
+        .map(|glyph| {
+            glyphs
+                .iter()
+                .find(|(_, known)| known == glyph)
+                .map(|(letter, _)| *letter)
+        })
+        .collect()
+}
+
+// Sizes of the 4-connected figures formed by the set cells, largest first.
+pub fn figure_sizes(grid: &BitGrid) -> Vec<usize> {
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
+    let mut sizes = Vec::new();
+    for start in grid.iter_ones() {
+        if visited.get_coord(&start) {
+            continue;
+        }
+        visited.set_coord(&start, true);
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(coord) = stack.pop() {
+            size += 1;
+            for neighbour in coord.get_neighbours() {
+                if grid.get_coord(&neighbour) && !visited.get_coord(&neighbour) {
+                    visited.set_coord(&neighbour, true);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+pub fn largest_figure(grid: &BitGrid) -> usize {
+    figure_sizes(grid).first().copied().unwrap_or(0)
+}
+
+// Whether some connected figure covers at least `min_size` cells, e.g. a picture
+// standing out from otherwise scattered points.
+pub fn contains_figure(grid: &BitGrid, min_size: usize) -> bool {
+    largest_figure(grid) >= min_size
+}
+
+// Renders the grid with `#` for set cells, handy for printing picture answers.
+pub fn render(grid: &BitGrid) -> String {
+    (0..grid.rows())
+        .map(|row| {
+            (0..grid.cols())
+                .map(|col| if grid.get(row, col) { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(picture: &str) -> BitGrid {
+        BitGrid::from_matrix(&crate::matrix::parse_matrix(picture), |c| *c == '#')
+    }
+
+    #[test]
+    fn reads_small_letters() {
+        let picture = "\
+..........................
+.#..#.###..####.#.....##..
+.#..#.#..#.#....#....#..#.
+.####.###..###..#....#..#.
+.#..#.#..#.#....#....#..#.
+.#..#.#..#.#....#....#..#.
+.#..#.###..####.####..##..
+..........................";
+        assert_eq!(recognize(&grid(picture)), Some("HBELO".to_string()));
+        assert_eq!(
+            recognize(&grid(
+                "#...#..##.\n#...#.#..#\n.#.#..#...\n..#...#...\n..#...#..#\n..#....##."
+            )),
+            Some("YC".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_large_letters() {
+        let picture = FONT_10
+            .iter()
+            .filter(|(letter, _)| "NXZ".contains(*letter))
+            .map(|(_, picture)| crate::matrix::parse_matrix(picture))
+            .reduce(|mut text, letter| {
+                for (row, letter_row) in text.iter_mut().zip(letter) {
+                    row.extend(['.', '.']);
+                    row.extend(letter_row);
+                }
+                text
+            })
+            .unwrap();
+        let grid = BitGrid::from_matrix(&picture, |c| *c == '#');
+        assert_eq!(recognize(&grid), Some("NXZ".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        assert_eq!(recognize(&grid("....\n....")), None);
+        assert_eq!(recognize(&grid("###\n#.#\n###")), None);
+        assert_eq!(recognize(&grid("####\n####\n####\n####\n####\n####")), None);
+    }
+
+    #[test]
+    fn finds_figures() {
+        let g = grid("#..##\n...##\n#.#..\n..###");
+        assert_eq!(figure_sizes(&g), vec![4, 4, 1, 1]);
+        assert!(contains_figure(&g, 4));
+        assert!(!contains_figure(&g, 5));
+        assert_eq!(largest_figure(&BitGrid::new(3, 3)), 0);
+        assert_eq!(render(&g), "#..##\n...##\n#.#..\n..###");
+    }
+}
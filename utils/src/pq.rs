@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A binary min-heap holding each key at most once, so searches lower a key's priority
+// in place instead of pushing duplicates and skipping stale entries when popping.
+#[derive(Debug, Clone)]
+pub struct IndexedMinHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord + Copy> Default for IndexedMinHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord + Copy> IndexedMinHeap<K, P> {
+    pub fn new() -> Self {
+        IndexedMinHeap {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<P> {
+        self.positions.get(key).map(|i| self.heap[*i].1)
+    }
+
+    pub fn peek_min(&self) -> Option<(&K, P)> {
+        self.heap.first().map(|(key, priority)| (key, *priority))
+    }
+
+    // Inserts `key`, or lowers its priority if it is queued with a higher one. Returns
+    // whether the queue changed.
+    pub fn push_or_decrease(&mut self, key: K, priority: P) -> bool {
+        let i = match self.positions.get(&key) {
+            Some(&i) if priority < self.heap[i].1 => {
+                self.heap[i].1 = priority;
+                i
+            }
+            Some(_) => return false,
+            None => {
+                self.positions.insert(key.clone(), self.heap.len());
+                self.heap.push((key, priority));
+                self.heap.len() - 1
+            }
+        };
+        self.sift_up(i);
+        true
+    }
+
+    // Ties come out in no particular order.
+    pub fn pop_min(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (key, priority) = self.heap.pop()?;
+        self.positions.remove(&key);
+        self.sift_down(0);
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        *self.positions.get_mut(&self.heap[i].0).unwrap() = i;
+        *self.positions.get_mut(&self.heap[j].0).unwrap() = j;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[parent].1 <= self.heap[i].1 {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let smallest = [2 * i + 1, 2 * i + 2]
+                .into_iter()
+                .filter(|child| *child < self.heap.len())
+                .fold(i, |smallest, child| {
+                    if self.heap[child].1 < self.heap[smallest].1 {
+                        child
+                    } else {
+                        smallest
+                    }
+                });
+            if smallest == i {
+                return;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_priority_order() {
+        let mut heap = IndexedMinHeap::new();
+        for (key, priority) in [("e", 5), ("b", 2), ("d", 4), ("a", 1), ("c", 3)] {
+            assert!(heap.push_or_decrease(key, priority));
+        }
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.peek_min(), Some((&"a", 1)));
+        let order = std::iter::from_fn(|| heap.pop_min()).collect::<Vec<_>>();
+        assert_eq!(order, [("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)]);
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop_min(), None);
+    }
+
+    #[test]
+    fn decreases_keys() {
+        let mut heap = IndexedMinHeap::new();
+        heap.push_or_decrease('x', 10);
+        heap.push_or_decrease('y', 20);
+        heap.push_or_decrease('z', 30);
+        assert!(!heap.push_or_decrease('x', 15));
+        assert!(!heap.push_or_decrease('x', 10));
+        assert!(heap.push_or_decrease('z', 5));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.priority(&'z'), Some(5));
+        assert_eq!(heap.pop_min(), Some(('z', 5)));
+        assert!(!heap.contains(&'z'));
+        assert_eq!(heap.priority(&'z'), None);
+        // Popped keys can be queued again.
+        assert!(heap.push_or_decrease('z', 15));
+        assert_eq!(heap.pop_min(), Some(('x', 10)));
+        assert_eq!(heap.pop_min(), Some(('z', 15)));
+        assert_eq!(heap.pop_min(), Some(('y', 20)));
+    }
+
+    #[test]
+    fn matches_a_sort() {
+        // Pseudo-random priorities, each key decreased a few times.
+        let mut heap = IndexedMinHeap::new();
+        let mut expected = HashMap::new();
+        let mut value = 12345u64;
+        for _ in 0..2000 {
+            value = value
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let key = (value >> 33) % 300;
+            let priority = (value >> 17) % 10_000;
+            heap.push_or_decrease(key, priority);
+            let best = expected.entry(key).or_insert(priority);
+            *best = (*best).min(priority);
+        }
+        let mut expected = expected
+            .into_iter()
+            .map(|(k, p)| (p, k))
+            .collect::<Vec<_>>();
+        expected.sort();
+        let mut popped = std::iter::from_fn(|| heap.pop_min())
+            .map(|(k, p)| (p, k))
+            .collect::<Vec<_>>();
+        assert_eq!(popped.len(), expected.len());
+        assert!(popped.windows(2).all(|w| w[0].0 <= w[1].0));
+        popped.sort();
+        assert_eq!(popped, expected);
+    }
+}
@@ -0,0 +1,375 @@
+use crate::coord::{BoundingBox, Coord};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// A region is an arbitrary set of unit cells; cell (x, y) covers the square between
+// the lattice points (x, y) and (x + 1, y + 1). Cells that only touch diagonally are
+// not connected, so their complement is treated as 8-connected: a hole has to be
+// sealed off by edge-sharing cells.
+
+const DIAGONALS: [Coord; 4] = [
+    Coord { x: 1, y: 1 },
+    Coord { x: 1, y: -1 },
+    Coord { x: -1, y: 1 },
+    Coord { x: -1, y: -1 },
+];
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Coord>,
+}
+
+impl Polygon {
+    // Shoelace formula: outer outlines are counter-clockwise and positive, holes negative.
+    pub fn signed_area(&self) -> i64 {
+        let n = self.vertices.len();
+        (0..n)
+            .map(|i| {
+                let a = self.vertices[i];
+                let b = self.vertices[(i + 1) % n];
+                a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64
+            })
+            .sum::<i64>()
+            / 2
+    }
+
+    pub fn is_hole(&self) -> bool {
+        self.signed_area() < 0
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Region {
+    cells: HashSet<Coord>,
+}
+
+impl FromIterator<Coord> for Region {
+    fn from_iter<I: IntoIterator<Item = Coord>>(iter: I) -> Self {
+        Region {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Region {
+    pub fn contains(&self, coord: &Coord) -> bool {
+        self.cells.contains(coord)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &Coord> {
+        self.cells.iter()
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|c| c.get_neighbours())
+            .filter(|n| !self.contains(n))
+            .count()
+    }
+
+    pub fn corners(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|c| {
+                DIAGONALS
+                    .iter()
+                    .filter(|d| {
+                        let horizontal = self.contains(&Coord::new(c.x + d.x, c.y));
+                        let vertical = self.contains(&Coord::new(c.x, c.y + d.y));
+                        let diagonal = self.contains(&(c + **d));
+                        let convex = !horizontal && !vertical;
+                        let concave = horizontal && vertical && !diagonal;
+                        convex || concave
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    // Every corner of a rectilinear outline starts a new side.
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_coords(&self.cells)
+    }
+
+    pub fn holes(&self) -> Vec<Region> {
+        let Some(mut bounds) = self.bounding_box() else {
+            return Vec::new();
+        };
+        bounds.extend(&bounds.min + Coord::new(-1, -1));
+        bounds.extend(&bounds.max + Coord::new(1, 1));
+
+        let mut outside = HashSet::new();
+        let mut work = VecDeque::from([bounds.min]);
+        outside.insert(bounds.min);
+        while let Some(c) = work.pop_front() {
+            for n in Self::get_8_neighbours(c) {
+                if bounds.contains(&n) && !self.contains(&n) && outside.insert(n) {
+                    work.push_back(n);
+                }
+            }
+        }
+
+        let mut holes = Vec::new();
+        let mut seen = outside;
+        for c in bounds.coords() {
+            if self.contains(&c) || seen.contains(&c) {
+                continue;
+            }
+            let mut hole = vec![c];
+            seen.insert(c);
+            let mut i = 0;
+            while i < hole.len() {
+                for n in Self::get_8_neighbours(hole[i]) {
+                    if !self.contains(&n) && seen.insert(n) {
+                        hole.push(n);
+                    }
+                }
+                i += 1;
+            }
+            holes.push(hole.into_iter().collect());
+        }
+        holes
+    }
+
+    pub fn has_holes(&self) -> bool {
+        !self.holes().is_empty()
+    }
+
+    // Traces the boundary edges with the region on their left. At a point where two
+    // cells touch diagonally the walk turns left, keeping those cells apart, so every
+    // returned polygon is simple apart from such touching vertices.
+    pub fn outlines(&self) -> Vec<Polygon> {
+        let mut edges: HashMap<Coord, Vec<Coord>> = HashMap::new();
+        for c in &self.cells {
+            let sides = [
+                (Coord::new(0, -1), Coord::new(c.x, c.y), Coord::new(1, 0)),
+                (Coord::new(1, 0), Coord::new(c.x + 1, c.y), Coord::new(0, 1)),
+                (
+                    Coord::new(0, 1),
+                    Coord::new(c.x + 1, c.y + 1),
+                    Coord::new(-1, 0),
+                ),
+                (
+                    Coord::new(-1, 0),
+                    Coord::new(c.x, c.y + 1),
+                    Coord::new(0, -1),
+                ),
+            ];
+            for (neighbour, start, dir) in sides {
+                if !self.contains(&(c + neighbour)) {
+                    edges.entry(start).or_default().push(dir);
+                }
+            }
+        }
+
+        let mut outlines = Vec::new();
+        while let Some(&start) = edges.keys().min() {
+            let mut vertices = Vec::new();
+            let mut pos = start;
+            let mut dir = Self::take_edge(&mut edges, pos, None);
+            let start_dir = dir;
+            loop {
+                pos += dir;
+                if pos == start {
+                    let mut candidates = edges.get(&start).cloned().unwrap_or_default();
+                    candidates.push(start_dir);
+                    if Self::preferred(&candidates, dir) == candidates.len() - 1 {
+                        if dir != start_dir {
+                            vertices.push(start);
+                        }
+                        break;
+                    }
+                }
+                let next = Self::take_edge(&mut edges, pos, Some(dir));
+                if next != dir {
+                    vertices.push(pos);
+                }
+                dir = next;
+            }
+            vertices.rotate_right(1);
+            outlines.push(Polygon { vertices });
+        }
+        outlines
+    }
+
+    fn take_edge(
+        edges: &mut HashMap<Coord, Vec<Coord>>,
+        at: Coord,
+        incoming: Option<Coord>,
+    ) -> Coord {
+        let dirs = edges.get_mut(&at).expect("outline should be closed");
+        let i = incoming.map_or(0, |d| Self::preferred(dirs, d));
+        let dir = dirs.swap_remove(i);
+        if dirs.is_empty() {
+            edges.remove(&at);
+        }
+        dir
+    }
+
+    // Left turn first, then straight ahead, then right turn.
+    fn preferred(dirs: &[Coord], incoming: Coord) -> usize {
+        let d = incoming;
+        [Coord::new(-d.y, d.x), d, Coord::new(d.y, -d.x)]
+            .iter()
+            .find_map(|p| dirs.iter().position(|dir| dir == p))
+            .expect("outline should be closed")
+    }
+
+    fn get_8_neighbours(c: Coord) -> impl Iterator<Item = Coord> {
+        c.get_neighbours()
+            .into_iter()
+            .chain(DIAGONALS.iter().map(move |d| &c + *d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(picture: &str) -> Region {
+        crate::matrix::parse_matrix(picture)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == '#')
+                    .map(move |(j, _)| Coord::new(i as i32, j as i32))
+            })
+            .collect()
+    }
+
+    fn regions_of(input: &str) -> Vec<Region> {
+        let matrix = crate::matrix::parse_matrix(input);
+        let mut seen = HashSet::new();
+        let mut regions = Vec::new();
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, plant) in row.iter().enumerate() {
+                let start = Coord::new(i as i32, j as i32);
+                if !seen.insert(start) {
+                    continue;
+                }
+                let mut cells = vec![start];
+                let mut k = 0;
+                while k < cells.len() {
+                    for n in cells[k].get_neighbours() {
+                        let same = matrix.get(n.x as usize).and_then(|r| r.get(n.y as usize))
+                            == Some(plant);
+                        if same && seen.insert(n) {
+                            cells.push(n);
+                        }
+                    }
+                    k += 1;
+                }
+                regions.push(cells.into_iter().collect());
+            }
+        }
+        regions
+    }
+
+    fn check_outlines(region: &Region) {
+        let outlines = region.outlines();
+        let area: i64 = outlines.iter().map(|p| p.signed_area()).sum();
+        let vertices: usize = outlines.iter().map(|p| p.vertices.len()).sum();
+        assert_eq!(area, region.area() as i64);
+        assert_eq!(vertices, region.corners());
+    }
+
+    fn price(input: &str, cost: fn(&Region) -> usize) -> usize {
+        regions_of(input)
+            .iter()
+            .inspect(|r| check_outlines(r))
+            .map(|r| r.area() * cost(r))
+            .sum()
+    }
+
+    #[test]
+    fn day12_examples() {
+        let small = "AAAA\nBBCD\nBBCC\nEEEC";
+        let e_shape = "EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE";
+        let tricky = "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA";
+        let holes = "OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO";
+        assert_eq!(price(small, Region::perimeter), 140);
+        assert_eq!(price(holes, Region::perimeter), 772);
+        assert_eq!(price(small, Region::sides), 80);
+        assert_eq!(price(e_shape, Region::sides), 236);
+        assert_eq!(price(tricky, Region::sides), 368);
+        assert_eq!(price(holes, Region::sides), 436);
+    }
+
+    #[test]
+    fn single_cell() {
+        let cell = region("#");
+        assert_eq!(cell.area(), 1);
+        assert_eq!(cell.perimeter(), 4);
+        assert_eq!(cell.sides(), 4);
+        assert_eq!(
+            cell.outlines(),
+            vec![Polygon {
+                vertices: vec![
+                    Coord::new(0, 0),
+                    Coord::new(1, 0),
+                    Coord::new(1, 1),
+                    Coord::new(0, 1)
+                ]
+            }]
+        );
+        assert!(!cell.has_holes());
+    }
+
+    #[test]
+    fn ring_with_hole() {
+        let ring = region("###\n#.#\n###");
+        assert_eq!(ring.area(), 8);
+        assert_eq!(ring.perimeter(), 16);
+        assert_eq!(ring.sides(), 8);
+        assert_eq!(ring.holes(), vec![region("...\n.#.")]);
+        let outlines = ring.outlines();
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(outlines.iter().filter(|p| p.is_hole()).count(), 1);
+        assert_eq!(
+            ring.bounding_box(),
+            Some(BoundingBox::new(Coord::new(0, 0), Coord::new(2, 2)))
+        );
+        check_outlines(&ring);
+    }
+
+    #[test]
+    fn diagonal_touching() {
+        let pair = region("#.\n.#");
+        assert_eq!(pair.sides(), 8);
+        assert_eq!(pair.outlines().len(), 2);
+        check_outlines(&pair);
+
+        let diamond = region(".#.\n#.#\n.#.");
+        assert_eq!(diamond.sides(), 16);
+        assert!(!diamond.has_holes());
+        assert_eq!(diamond.outlines().len(), 4);
+        check_outlines(&diamond);
+
+        let pinched = region("#####\n#.#.#\n##.##\n#.#.#\n#####");
+        assert_eq!(pinched.holes().len(), 1);
+        assert_eq!(pinched.holes()[0].area(), 5);
+        assert_eq!(pinched.outlines().iter().filter(|p| p.is_hole()).count(), 1);
+        check_outlines(&pinched);
+    }
+
+    #[test]
+    fn empty_region() {
+        let empty = Region::default();
+        assert_eq!(empty.area(), 0);
+        assert_eq!(empty.bounding_box(), None);
+        assert!(empty.holes().is_empty());
+        assert!(empty.outlines().is_empty());
+    }
+}
@@ -0,0 +1,829 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+struct Param {
+    name: &'static str,
+    help: &'static str,
+    type_name: &'static str,
+    type_id: TypeId,
+    default: String,
+    check: fn(&str) -> Result<(), String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgError {
+    HelpRequested,
+    UnknownArgument(String),
+    MissingValue(String),
+    InvalidValue {
+        name: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgError::HelpRequested => write!(f, "help requested"),
+            ArgError::UnknownArgument(arg) => write!(f, "unknown argument {}", arg),
+            ArgError::MissingValue(name) => write!(f, "missing value for --{}", name),
+            ArgError::InvalidValue {
+                name,
+                value,
+                reason,
+            } => write!(f, "invalid value {:?} for --{}: {}", value, name, reason),
+        }
+    }
+}
+
+fn check_parse<T: FromStr>(value: &str) -> Result<(), String>
+where
+    T::Err: Display,
+{
+    value.parse::<T>().map(|_| ()).map_err(|e| e.to_string())
+}
+
+// Answers are cached per day in `<dir>/<day>.cache`, one `key\tanswer` line each.
+// The key starts with a hash of the day's source, utils' sources and the enabled
+// features, so editing a day only invalidates that day's entries, and those are dropped
+// on the next write.
+struct Cache {
+    dir: PathBuf,
+    source_hash: u64,
+    input_hash: u64,
+}
+
+impl Cache {
+    fn default_dir() -> PathBuf {
+        std::env::var_os("AOC_CACHE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                PathBuf::from(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../target/answer-cache"
+                ))
+            })
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.cache", name))
+    }
+
+    fn load(&self, name: &str, key: &str) -> Option<String> {
+        let content = std::fs::read_to_string(self.file(name)).ok()?;
+        content.lines().find_map(|line| {
+            let (line_key, answer) = line.split_once('\t')?;
+            (line_key == key).then(|| answer.to_string())
+        })
+    }
+
+    fn store(&self, name: &str, key: &str, answer: &str) -> std::io::Result<()> {
+        if answer.contains(['\n', '\t']) {
+            return Ok(());
+        }
+        let version = format!("{:016x}-", self.source_hash);
+        let entry = format!("{}\t", key);
+        let content = std::fs::read_to_string(self.file(name)).unwrap_or_default();
+        let mut lines = content
+            .lines()
+            .filter(|line| line.starts_with(&version) && !line.starts_with(&entry))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        lines.push(format!("{}\t{}", key, answer));
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.file(name), lines.join("\n") + "\n")
+    }
+}
+
+// The utils features a day can turn on. `checked` changes what a part checks, so it goes
+// into the cache key along with the others.
+const FEATURES: [(&str, bool); 4] = [
+    ("checked", cfg!(feature = "checked")),
+    ("image", cfg!(feature = "image")),
+    ("serde", cfg!(feature = "serde")),
+    ("tui", cfg!(feature = "tui")),
+];
+
+fn version_hash(source: &str, utils_hash: &str, features: &[&str]) -> u64 {
+    hash(format!("{}\0{}\0{}", source, utils_hash, features.join(",")).as_bytes())
+}
+
+// FNV-1a, stable across toolchains unlike the std hasher.
+fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn load_input(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "cannot read {}: {}\nsave your puzzle input there to run the day",
+            path.display(),
+            err
+        )
+    })
+}
+
+// The day's `src/input.txt`, read when it runs rather than built in, so the days build
+// and test without our inputs, which are not checked in. Exits if it is missing.
+pub fn read_input(manifest_dir: &str) -> &'static str {
+    let path = Path::new(manifest_dir).join("src").join("input.txt");
+    match load_input(&path) {
+        Ok(input) => input.leak(),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub struct Runner {
+    name: &'static str,
+    params: Vec<Param>,
+    values: HashMap<&'static str, String>,
+    cache: Option<Cache>,
+    use_cache: bool,
+    compare: bool,
+    #[cfg(feature = "serde")]
+    dump_dir: Option<PathBuf>,
+    #[cfg(feature = "image")]
+    image_dir: Option<PathBuf>,
+    #[cfg(feature = "tui")]
+    tui: bool,
+}
+
+impl Runner {
+    pub fn new(name: &'static str) -> Self {
+        Runner {
+            name,
+            params: Vec::new(),
+            values: HashMap::new(),
+            cache: None,
+            use_cache: true,
+            compare: false,
+            #[cfg(feature = "serde")]
+            dump_dir: None,
+            #[cfg(feature = "image")]
+            image_dir: None,
+            #[cfg(feature = "tui")]
+            tui: false,
+        }
+    }
+
+    // Enables the answer cache for this day; pass the day's `main.rs` and puzzle input.
+    pub fn cache(mut self, source: &str, input: &str) -> Self {
+        let features = FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        self.cache = Some(Cache {
+            dir: Cache::default_dir(),
+            source_hash: version_hash(source, env!("UTILS_SOURCE_HASH"), &features),
+            input_hash: hash(input.as_bytes()),
+        });
+        self
+    }
+
+    pub fn param<T: FromStr + Display + 'static>(
+        mut self,
+        name: &'static str,
+        default: T,
+        help: &'static str,
+    ) -> Self
+    where
+        T::Err: Display,
+    {
+        assert!(
+            self.params.iter().all(|p| p.name != name),
+            "duplicate parameter {}",
+            name
+        );
+        self.params.push(Param {
+            name,
+            help,
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            default: default.to_string(),
+            check: check_parse::<T>,
+        });
+        self
+    }
+
+    pub fn parse_args<I: IntoIterator<Item = String>>(mut self, args: I) -> Result<Self, ArgError> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Err(ArgError::HelpRequested);
+            }
+            if arg == "--no-cache" {
+                self.use_cache = false;
+                continue;
+            }
+            if arg == "--compare" {
+                self.compare = true;
+                continue;
+            }
+            #[cfg(feature = "tui")]
+            if arg == "--tui" {
+                self.tui = true;
+                continue;
+            }
+            #[cfg(feature = "serde")]
+            if let Some(dir) = dir_option(&arg, "dump-state", &mut args)? {
+                self.dump_dir = Some(dir);
+                continue;
+            }
+            #[cfg(feature = "image")]
+            if let Some(dir) = dir_option(&arg, "images", &mut args)? {
+                self.image_dir = Some(dir);
+                continue;
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                return Err(ArgError::UnknownArgument(arg));
+            };
+            let (name, inline_value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let Some(param) = self.params.iter().find(|p| p.name == name) else {
+                return Err(ArgError::UnknownArgument(arg));
+            };
+            let value = inline_value
+                .or_else(|| args.next())
+                .ok_or_else(|| ArgError::MissingValue(param.name.to_string()))?;
+            (param.check)(&value).map_err(|reason| ArgError::InvalidValue {
+                name: param.name.to_string(),
+                value: value.clone(),
+                reason,
+            })?;
+            self.values.insert(param.name, value);
+        }
+        Ok(self)
+    }
+
+    pub fn from_env(self) -> Self {
+        let usage = self.usage();
+        match self.parse_args(std::env::args().skip(1)) {
+            Ok(runner) => runner,
+            Err(ArgError::HelpRequested) => {
+                println!("{}", usage);
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("error: {}\n\n{}", err, usage);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // `T` has to be the type the parameter was declared with, even where the value would
+    // parse as another one, so a mismatch fails on the first run rather than on some values.
+    pub fn get<T: FromStr + 'static>(&self, name: &str) -> T
+    where
+        T::Err: Display,
+    {
+        let param = self
+            .params
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("undeclared parameter {}", name));
+        assert!(
+            param.type_id == TypeId::of::<T>(),
+            "parameter {} is declared as {} but read as {}",
+            name,
+            param.type_name,
+            std::any::type_name::<T>()
+        );
+        let value = self.values.get(param.name).unwrap_or(&param.default);
+        value
+            .parse::<T>()
+            .unwrap_or_else(|e| panic!("parameter {} is not a {}: {}", name, param.type_name, e))
+    }
+
+    // With `checked` the parts always run, as a cached answer would skip the overflow checks.
+    pub fn solve<R: Display + FromStr>(&self, part: &'static str, f: impl FnOnce() -> R) -> R {
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| self.use_cache && !cfg!(feature = "checked"));
+        let key = cache.map(|cache| self.cache_key(cache, part));
+        if let Some(answer) = cache
+            .zip(key.as_deref())
+            .and_then(|(cache, key)| cache.load(self.name, key))
+            .and_then(|answer| answer.parse().ok())
+        {
+            return answer;
+        }
+        let answer = self.time(&format!("part {}", part), || {
+            crate::checked::with_context(self.name, part, f)
+        });
+        if let Some((cache, key)) = cache.zip(key) {
+            if let Err(err) = cache.store(self.name, &key, &answer.to_string()) {
+                eprintln!("warning: could not write the answer cache: {}", err);
+            }
+        }
+        answer
+    }
+
+    // Runs the day's input checks before any part. Lists every problem and exits if
+    // there are any.
+    pub fn validate(&self, f: impl FnOnce() -> crate::validate::Diagnostics) {
+        let diagnostics = self.time("validate", f);
+        if !diagnostics.is_empty() {
+            eprint!("{}: invalid input\n{}", self.name, diagnostics);
+            std::process::exit(1);
+        }
+    }
+
+    // Registers several solutions of `part`, see `Variants`.
+    pub fn variants<'f, R>(&self, part: &'static str) -> Variants<'_, 'f, R> {
+        Variants {
+            runner: self,
+            part,
+            variants: Vec::new(),
+        }
+    }
+
+    // Reports how long `f` took on stderr when `AOC_TIMINGS` is set, e.g. for parsing.
+    pub fn time<R>(&self, step: &str, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        if std::env::var_os("AOC_TIMINGS").is_some() {
+            eprintln!(
+                "{} {} took {}us",
+                self.name,
+                step,
+                start.elapsed().as_micros()
+            );
+        }
+        result
+    }
+
+    // Writes `<AOC_SVG_DIR>/<day>.svg` when the report asks for it; `f` is not run otherwise.
+    pub fn visualise(&self, f: impl FnOnce() -> String) {
+        let Some(dir) = std::env::var_os("AOC_SVG_DIR") else {
+            return;
+        };
+        let path = PathBuf::from(dir).join(format!("{}.svg", self.name));
+        if let Err(err) = std::fs::write(&path, f()) {
+            eprintln!("warning: could not write {}: {}", path.display(), err);
+        }
+    }
+
+    // With `--dump-state <dir>`, writes the state `f` builds to `<dir>/<day>-<label>.json`
+    // for debugging or other tools. `f` only runs when dumping.
+    #[cfg(feature = "serde")]
+    pub fn dump_state<T: serde::Serialize>(&self, label: &str, f: impl FnOnce() -> T) {
+        let Some(dir) = &self.dump_dir else {
+            return;
+        };
+        let path = dir.join(format!("{}-{}.json", self.name, label));
+        let json = serde_json::to_string_pretty(&f()).expect("state serializes to JSON");
+        if let Err(err) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, json)) {
+            eprintln!("warning: could not write {}: {}", path.display(), err);
+        }
+    }
+
+    // With `--images <dir>`, writes the picture `f` draws to `<dir>/<day>-<label>.png`.
+    // `f` only runs when exporting.
+    #[cfg(feature = "image")]
+    pub fn image(&self, label: &str, f: impl FnOnce() -> crate::image::Image) {
+        self.export(label, "png", |path| f().save(path));
+    }
+
+    // Like `image`, for an animation written as `<dir>/<day>-<label>.gif`.
+    #[cfg(feature = "image")]
+    pub fn animation(&self, label: &str, f: impl FnOnce() -> crate::image::Animation) {
+        self.export(label, "gif", |path| f().save(path));
+    }
+
+    #[cfg(feature = "image")]
+    fn export(
+        &self,
+        label: &str,
+        extension: &str,
+        save: impl FnOnce(&std::path::Path) -> std::io::Result<()>,
+    ) {
+        let Some(dir) = &self.image_dir else {
+            return;
+        };
+        let path = dir.join(format!("{}-{}.{}", self.name, label, extension));
+        if let Err(err) = std::fs::create_dir_all(dir).and_then(|_| save(&path)) {
+            eprintln!("warning: could not write {}: {}", path.display(), err);
+        }
+    }
+
+    // With `--tui`, plays the simulation `f` builds in the terminal instead of solving,
+    // and exits once the user quits.
+    #[cfg(feature = "tui")]
+    pub fn tui<S: crate::sim::Simulation + Clone>(&self, f: impl FnOnce() -> S) {
+        if !self.tui {
+            return;
+        }
+        if let Err(err) = crate::tui::play(self.name, f()) {
+            eprintln!("{}: terminal error: {}", self.name, err);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    // Source, input and parameter values all go into the key.
+    fn cache_key(&self, cache: &Cache, part: &str) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|p| {
+                let value = self.values.get(p.name).unwrap_or(&p.default);
+                format!("{}={}", p.name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "{:016x}-{:016x}-{:016x}-{}",
+            cache.source_hash,
+            cache.input_hash,
+            hash(params.as_bytes()),
+            part
+        )
+    }
+
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: {} [OPTIONS]\n\nOptions:\n", self.name);
+        for param in &self.params {
+            usage += &format!(
+                "  --{} <{}>\n          {} [default: {}]\n",
+                param.name, param.type_name, param.help, param.default
+            );
+        }
+        if self.cache.is_some() {
+            usage += "  --no-cache\n          Recompute answers instead of reading them from the cache\n";
+        }
+        usage += "  --compare\n          Run every variant of a part, check that they agree and compare timings\n";
+        #[cfg(feature = "tui")]
+        {
+            usage +=
+                "  --tui\n          Play the day's simulation in the terminal instead of solving\n";
+        }
+        #[cfg(feature = "serde")]
+        {
+            usage += "  --dump-state <dir>\n          Write the day's intermediate state to <dir> as JSON\n";
+        }
+        #[cfg(feature = "image")]
+        {
+            usage +=
+                "  --images <dir>\n          Write the day's pictures and animations to <dir>\n";
+        }
+        usage += "  -h, --help\n          Print help\n";
+        usage
+    }
+}
+
+// `--<name> <dir>` or `--<name>=<dir>`; None for any other argument.
+#[cfg(any(feature = "serde", feature = "image"))]
+fn dir_option(
+    arg: &str,
+    name: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<Option<PathBuf>, ArgError> {
+    let Some(dir) = arg.strip_prefix("--").and_then(|a| a.strip_prefix(name)) else {
+        return Ok(None);
+    };
+    let dir = match dir.strip_prefix('=') {
+        Some(dir) => dir.to_string(),
+        None if dir.is_empty() => args
+            .next()
+            .ok_or_else(|| ArgError::MissingValue(name.to_string()))?,
+        None => return Ok(None),
+    };
+    Ok(Some(PathBuf::from(dir)))
+}
+
+// Alternative solutions of one part, e.g. a library algorithm and a hand-written one.
+// Normally only the first variant runs, exactly like `Runner::solve`. With `--compare`
+// all of them run, their answers must agree and their timings go to stderr side by side.
+pub struct Variants<'r, 'f, R> {
+    runner: &'r Runner,
+    part: &'static str,
+    variants: Vec<(&'static str, Solution<'f, R>)>,
+}
+
+type Solution<'f, R> = Box<dyn FnOnce() -> R + 'f>;
+
+impl<'f, R: Display + FromStr + PartialEq> Variants<'_, 'f, R> {
+    pub fn variant(mut self, name: &'static str, f: impl FnOnce() -> R + 'f) -> Self {
+        assert!(
+            self.variants.iter().all(|(n, _)| *n != name),
+            "duplicate variant {}",
+            name
+        );
+        self.variants.push((name, Box::new(f)));
+        self
+    }
+
+    pub fn solve(self) -> R {
+        let Variants {
+            runner,
+            part,
+            variants,
+        } = self;
+        assert!(!variants.is_empty(), "no variants of part {}", part);
+        if !runner.compare {
+            let (_, f) = variants.into_iter().next().unwrap();
+            return runner.solve(part, f);
+        }
+        let runs = variants
+            .into_iter()
+            .map(|(name, f)| {
+                let start = Instant::now();
+                let answer = crate::checked::with_context(runner.name, part, f);
+                (name, answer, start.elapsed())
+            })
+            .collect::<Vec<_>>();
+        let table = compare_table(&runs);
+        if runs.iter().any(|(_, answer, _)| *answer != runs[0].1) {
+            panic!(
+                "{} part {}: variants disagree\n{}",
+                runner.name, part, table
+            );
+        }
+        eprintln!("{} part {} variants:\n{}", runner.name, part, table);
+        runs.into_iter().next().unwrap().1
+    }
+}
+
+// One line per variant: name, answer, time and time relative to the first variant.
+fn compare_table<R: Display>(runs: &[(&str, R, Duration)]) -> String {
+    let answers = runs
+        .iter()
+        .map(|(_, answer, _)| answer.to_string())
+        .collect::<Vec<_>>();
+    let name_width = runs
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let answer_width = answers.iter().map(String::len).max().unwrap_or(0);
+    let baseline = runs.first().map_or(Duration::ZERO, |(_, _, took)| *took);
+    runs.iter()
+        .zip(&answers)
+        .map(|((name, _, took), answer)| {
+            let relative = took.as_secs_f64() / baseline.as_secs_f64().max(f64::MIN_POSITIVE);
+            format!(
+                "  {:name_width$}  {:answer_width$}  {:>10.2?}  {:.2}x",
+                name, answer, took, relative
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner() -> Runner {
+        Runner::new("day00")
+            .param("size", 71, "size of the grid")
+            .param("offset", 10000000000000i128, "prize offset")
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn missing_input() {
+        let path = Path::new("/nonexistent/src/input.txt");
+        let err = load_input(path).unwrap_err();
+        assert!(
+            err.starts_with("cannot read /nonexistent/src/input.txt: "),
+            "{}",
+            err
+        );
+        assert!(
+            err.ends_with("\nsave your puzzle input there to run the day"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        let runner = runner().parse_args(args(&[])).unwrap();
+        assert_eq!(runner.get::<i32>("size"), 71);
+        assert_eq!(runner.get::<i128>("offset"), 10000000000000);
+    }
+
+    #[test]
+    fn overrides() {
+        let runner = runner()
+            .parse_args(args(&["--size", "7", "--offset=0"]))
+            .unwrap();
+        assert_eq!(runner.get::<i32>("size"), 7);
+        assert_eq!(runner.get::<i128>("offset"), 0);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            runner().parse_args(args(&["--help"])).err(),
+            Some(ArgError::HelpRequested)
+        );
+        assert_eq!(
+            runner().parse_args(args(&["--width", "3"])).err(),
+            Some(ArgError::UnknownArgument("--width".to_string()))
+        );
+        assert_eq!(
+            runner().parse_args(args(&["--size"])).err(),
+            Some(ArgError::MissingValue("size".to_string()))
+        );
+        assert!(matches!(
+            runner().parse_args(args(&["--size", "big"])).err(),
+            Some(ArgError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter size is declared as i32 but read as u64")]
+    fn reads_params_as_declared() {
+        let runner = runner().parse_args(args(&[])).unwrap();
+        runner.get::<u64>("size");
+    }
+
+    #[test]
+    fn usage_lists_params() {
+        let usage = runner().usage();
+        assert!(usage.contains("--size <i32>"));
+        assert!(usage.contains("[default: 71]"));
+        assert!(usage.contains("--offset <i128>"));
+    }
+
+    #[test]
+    fn runs_first_variant_by_default() {
+        let runner = runner().parse_args(args(&[])).unwrap();
+        let answer = runner
+            .variants("one")
+            .variant("fast", || 6)
+            .variant("slow", || unreachable!())
+            .solve();
+        assert_eq!(answer, 6);
+    }
+
+    #[test]
+    fn compares_variants() {
+        let runner = runner().parse_args(args(&["--compare"])).unwrap();
+        let ran = std::cell::Cell::new(0);
+        let answer = runner
+            .variants("one")
+            .variant("fast", || {
+                ran.set(ran.get() + 1);
+                6
+            })
+            .variant("slow", || {
+                ran.set(ran.get() + 1);
+                6
+            })
+            .solve();
+        assert_eq!((answer, ran.get()), (6, 2));
+        assert_eq!(
+            compare_table(&[
+                ("fast", 6, Duration::from_micros(500)),
+                ("slowest", 6, Duration::from_millis(2)),
+            ]),
+            "  fast     6    500.00µs  1.00x\n  slowest  6      2.00ms  4.00x"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "day00 part two: variants disagree")]
+    fn disagreeing_variants_panic() {
+        let runner = runner().parse_args(args(&["--compare"])).unwrap();
+        runner
+            .variants("two")
+            .variant("right", || 16)
+            .variant("wrong", || 15)
+            .solve();
+    }
+
+    fn cached_runner(dir: &std::path::Path, source: &str, input: &str) -> Runner {
+        let mut runner = runner().cache(source, input);
+        runner.cache.as_mut().unwrap().dir = dir.to_path_buf();
+        runner
+    }
+
+    #[cfg(not(feature = "checked"))]
+    #[test]
+    fn caches_answers() {
+        let dir = std::env::temp_dir().join(format!("runner-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let calls = std::cell::Cell::new(0);
+        let solve = |runner: &Runner, answer: u64| {
+            runner.solve("one", || {
+                calls.set(calls.get() + 1);
+                answer
+            })
+        };
+
+        let runner = cached_runner(&dir, "fn main() {}", "1 2 3");
+        assert_eq!(solve(&runner, 6), 6);
+        assert_eq!(solve(&runner, 7), 6);
+        assert_eq!(calls.get(), 1);
+
+        let other_input = cached_runner(&dir, "fn main() {}", "1 2 4");
+        assert_eq!(solve(&other_input, 7), 7);
+        let other_params = cached_runner(&dir, "fn main() {}", "1 2 3")
+            .parse_args(args(&["--size", "7"]))
+            .unwrap();
+        assert_eq!(solve(&other_params, 8), 8);
+        let bypassed = cached_runner(&dir, "fn main() {}", "1 2 3")
+            .parse_args(args(&["--no-cache"]))
+            .unwrap();
+        assert_eq!(solve(&bypassed, 9), 9);
+        assert_eq!(calls.get(), 4);
+        assert_eq!(solve(&runner, 10), 6);
+
+        let edited = cached_runner(&dir, "fn main() { edited }", "1 2 3");
+        assert_eq!(solve(&edited, 11), 11);
+        assert_eq!(calls.get(), 5);
+        let entries = std::fs::read_to_string(dir.join("day00.cache")).unwrap();
+        assert_eq!(entries.lines().count(), 1);
+
+        let coord = edited.solve("two", || crate::coord::Coord::new(6, 1));
+        assert_eq!(
+            coord,
+            edited.solve("two", || crate::coord::Coord::new(0, 0))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn versions_cover_utils_and_features() {
+        let version = version_hash("fn main() {}", "0123", &[]);
+        assert_eq!(version, version_hash("fn main() {}", "0123", &[]));
+        assert_ne!(version, version_hash("fn main() {}", "4567", &[]));
+        assert_ne!(version, version_hash("fn main() {}", "0123", &["checked"]));
+        assert_ne!(version, version_hash("fn main() { }", "0123", &[]));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn checked_builds_always_solve() {
+        let dir = std::env::temp_dir().join(format!("runner-checked-{}", std::process::id()));
+        let runner = cached_runner(&dir, "fn main() {}", "1 2 3");
+        assert_eq!(runner.solve("one", || 6), 6);
+        assert_eq!(runner.solve("one", || 7), 7);
+        assert!(!dir.exists());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dumps_state() {
+        let dir = std::env::temp_dir().join(format!("runner-dump-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        runner().dump_state("never", || -> u32 { unreachable!() });
+
+        let dir_arg = format!("--dump-state={}", dir.display());
+        let dumping = runner().parse_args(args(&[&dir_arg])).unwrap();
+        dumping.dump_state("robots", || vec![crate::coord::Coord::new(6, 1)]);
+        let json = std::fs::read_to_string(dir.join("day00-robots.json")).unwrap();
+        let robots: Vec<crate::coord::Coord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(robots, [crate::coord::Coord::new(6, 1)]);
+        assert_eq!(
+            runner().parse_args(args(&["--dump-state"])).err(),
+            Some(ArgError::MissingValue("dump-state".to_string()))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn exports_images() {
+        use crate::image::{Animation, Image, WHITE};
+        let dir = std::env::temp_dir().join(format!("runner-images-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        runner().image("never", || unreachable!());
+
+        let exporting = runner()
+            .parse_args(args(&["--images", dir.to_str().unwrap()]))
+            .unwrap();
+        exporting.image("still", || Image::new(2, 2, WHITE));
+        exporting.animation("moving", || {
+            let mut animation = Animation::new(std::time::Duration::from_millis(50));
+            animation.push(Image::new(2, 2, WHITE));
+            animation
+        });
+        let png = std::fs::read(dir.join("day00-still.png")).unwrap();
+        assert_eq!(png, Image::new(2, 2, WHITE).to_png());
+        assert!(std::fs::read(dir.join("day00-moving.gif"))
+            .unwrap()
+            .starts_with(b"GIF89a"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
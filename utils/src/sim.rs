@@ -0,0 +1,272 @@
+use crate::cycle::Cycle;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+// A puzzle that is a state plus a step: a guard walking, robots moving, a VM executing.
+pub trait Simulation {
+    // Advances one step; never called once the simulation is done.
+    fn step(&mut self);
+
+    fn is_done(&self) -> bool;
+
+    // Equal states have to hash equally. Repeat detection trusts the hash, so it should
+    // cover the whole state (see `hash_of`).
+    fn state_hash(&self) -> u64;
+
+    fn render(&self) -> String;
+
+    // Figures worth watching besides the step, e.g. the score or the visited cells.
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+pub fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Why a run of the driver stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Steps,
+    Done,
+    Condition,
+    // The state after `start + length` steps repeats the one after `start`.
+    Repeat(Cycle),
+}
+
+// Runs a simulation, counting steps. Optionally keeps a snapshot before every step so
+// it can be rewound, and watches for repeated states.
+pub struct Driver<S> {
+    sim: S,
+    steps: usize,
+    snapshot: Option<fn(&S) -> S>,
+    history: VecDeque<S>,
+    history_limit: usize,
+    seen: Option<HashMap<u64, usize>>,
+}
+
+impl<S: Simulation> Driver<S> {
+    pub fn new(sim: S) -> Self {
+        Driver {
+            sim,
+            steps: 0,
+            snapshot: None,
+            history: VecDeque::new(),
+            history_limit: usize::MAX,
+            seen: None,
+        }
+    }
+
+    pub fn with_history(mut self) -> Self
+    where
+        S: Clone,
+    {
+        self.snapshot = Some(S::clone);
+        self
+    }
+
+    // Keeps only the snapshots of the last `steps` steps, for big states. With 0 it takes
+    // none at all.
+    pub fn limit_history(mut self, steps: usize) -> Self {
+        self.history_limit = steps;
+        self
+    }
+
+    pub fn detect_repeats(mut self) -> Self {
+        self.seen = Some(HashMap::from([(self.sim.state_hash(), self.steps)]));
+        self
+    }
+
+    pub fn sim(&self) -> &S {
+        &self.sim
+    }
+
+    pub fn into_sim(self) -> S {
+        self.sim
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn advance(&mut self) -> Option<Stop> {
+        if self.sim.is_done() {
+            return Some(Stop::Done);
+        }
+        if let Some(snapshot) = self.snapshot.filter(|_| self.history_limit > 0) {
+            if self.history.len() >= self.history_limit {
+                self.history.pop_front();
+            }
+            self.history.push_back(snapshot(&self.sim));
+        }
+        self.sim.step();
+        self.steps += 1;
+        let seen = self.seen.as_mut()?;
+        match seen.entry(self.sim.state_hash()) {
+            Entry::Occupied(start) => Some(Stop::Repeat(Cycle {
+                start: *start.get(),
+                length: self.steps - start.get(),
+            })),
+            Entry::Vacant(entry) => {
+                entry.insert(self.steps);
+                None
+            }
+        }
+    }
+
+    // Runs `n` steps unless the simulation ends or repeats first.
+    pub fn run(&mut self, n: usize) -> Stop {
+        for _ in 0..n {
+            if let Some(stop) = self.advance() {
+                return stop;
+            }
+        }
+        Stop::Steps
+    }
+
+    // Runs until `condition` holds for the current state, checked before every step.
+    // Loops forever on a simulation that never ends unless repeats are detected.
+    pub fn run_until(&mut self, mut condition: impl FnMut(&S) -> bool) -> Stop {
+        loop {
+            if condition(&self.sim) {
+                return Stop::Condition;
+            }
+            if let Some(stop) = self.advance() {
+                return stop;
+            }
+        }
+    }
+
+    pub fn finish(&mut self) -> Stop {
+        self.run_until(|_| false)
+    }
+
+    // How many steps `rewind` can go back.
+    pub fn can_rewind(&self) -> usize {
+        self.history.len()
+    }
+
+    // Goes back up to `n` steps, as far as the history reaches; returns how many.
+    pub fn rewind(&mut self, n: usize) -> usize {
+        let n = n.min(self.history.len());
+        if n == 0 {
+            return 0;
+        }
+        self.sim = self.history.drain(self.history.len() - n..).next().unwrap();
+        self.steps -= n;
+        if let Some(seen) = &mut self.seen {
+            seen.retain(|_, step| *step <= self.steps);
+        }
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts 0, 1, 2, ... modulo `modulus`, done on reaching `end`.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Counter {
+        value: u32,
+        modulus: u32,
+        end: u32,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) {
+            self.value = (self.value + 1) % self.modulus;
+        }
+
+        fn is_done(&self) -> bool {
+            self.value == self.end
+        }
+
+        fn state_hash(&self) -> u64 {
+            hash_of(&self.value)
+        }
+
+        fn render(&self) -> String {
+            self.value.to_string()
+        }
+    }
+
+    fn counter(modulus: u32, end: u32) -> Counter {
+        Counter {
+            value: 0,
+            modulus,
+            end,
+        }
+    }
+
+    #[test]
+    fn runs_and_stops() {
+        let mut driver = Driver::new(counter(100, 10));
+        assert_eq!(driver.run(4), Stop::Steps);
+        assert_eq!(driver.sim().render(), "4");
+        assert_eq!(driver.run_until(|c| c.value == 7), Stop::Condition);
+        assert_eq!(driver.steps(), 7);
+        assert_eq!(driver.run(100), Stop::Done);
+        assert_eq!(driver.steps(), 10);
+        assert_eq!(driver.finish(), Stop::Done);
+        assert_eq!(driver.into_sim().value, 10);
+    }
+
+    #[test]
+    fn detects_repeats() {
+        let mut driver = Driver::new(counter(5, 99)).detect_repeats();
+        assert_eq!(driver.run(3), Stop::Steps);
+        assert_eq!(
+            driver.finish(),
+            Stop::Repeat(Cycle {
+                start: 0,
+                length: 5
+            })
+        );
+        assert_eq!(Driver::new(counter(5, 99)).run(1000), Stop::Steps);
+    }
+
+    #[test]
+    fn rewinds() {
+        let mut driver = Driver::new(counter(5, 99)).with_history().detect_repeats();
+        driver.run(3);
+        assert_eq!(driver.rewind(2), 2);
+        assert_eq!((driver.steps(), driver.sim().value), (1, 1));
+        assert_eq!(driver.run(2), Stop::Steps);
+        assert_eq!(driver.sim().value, 3);
+        assert_eq!(driver.rewind(10), 3);
+        assert_eq!(driver.sim(), &counter(5, 99));
+        assert_eq!(driver.rewind(1), 0);
+        assert_eq!(
+            driver.finish(),
+            Stop::Repeat(Cycle {
+                start: 0,
+                length: 5
+            })
+        );
+        assert_eq!(Driver::new(counter(5, 99)).rewind(1), 0);
+
+        let mut driver = Driver::new(counter(100, 99))
+            .with_history()
+            .limit_history(4);
+        driver.run(10);
+        assert_eq!(driver.can_rewind(), 4);
+        assert_eq!(driver.rewind(10), 4);
+        assert_eq!(driver.sim().value, 6);
+    }
+
+    #[test]
+    fn zero_history_limit() {
+        let mut driver = Driver::new(counter(100, 99))
+            .with_history()
+            .limit_history(0);
+        assert_eq!(driver.run(10), Stop::Steps);
+        assert_eq!(driver.can_rewind(), 0);
+        assert_eq!(driver.rewind(1), 0);
+        assert_eq!(driver.sim().value, 10);
+    }
+}
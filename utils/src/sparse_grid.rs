@@ -0,0 +1,241 @@
+use crate::coord::{BoundingBox, Coord};
+use std::collections::HashMap;
+use std::ops::Index;
+
+// Grid storing only the cells that were written; every other coordinate reads as the
+// default value. Rows are `x` and columns `y`, matching `Coord::new(i, j)` over a
+// `parse_matrix` result.
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    #[cfg_attr(feature = "serde", serde(with = "cells"))]
+    cells: HashMap<Coord, T>,
+    default: T,
+    bounds: Option<BoundingBox>,
+    torus: Option<BoundingBox>,
+}
+
+impl<T: Clone> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+            torus: None,
+        }
+    }
+
+    // Every coordinate is wrapped onto `0..rows` and `0..cols`.
+    pub fn toroidal(default: T, rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0, "torus must not be empty");
+        let torus = BoundingBox::new(
+            Coord::new(0, 0),
+            Coord::new(rows as i32 - 1, cols as i32 - 1),
+        );
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: Some(torus),
+            torus: Some(torus),
+        }
+    }
+
+    pub fn from_dense(matrix: &[Vec<T>], default: T) -> Self
+    where
+        T: PartialEq,
+    {
+        let mut grid = SparseGrid::new(default);
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let coord = Coord::new(i as i32, j as i32);
+                grid.extend_bounds(coord);
+                if *value != grid.default {
+                    grid.cells.insert(coord, value.clone());
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn wrap(&self, coord: Coord) -> Coord {
+        match self.torus {
+            Some(torus) => Coord::new(
+                coord.x.rem_euclid(torus.max.x + 1),
+                coord.y.rem_euclid(torus.max.y + 1),
+            ),
+            None => coord,
+        }
+    }
+
+    pub fn get(&self, coord: Coord) -> &T {
+        self.cells.get(&self.wrap(coord)).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> &mut T {
+        let coord = self.wrap(coord);
+        self.extend_bounds(coord);
+        self.cells
+            .entry(coord)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn set(&mut self, coord: Coord, value: T) -> Option<T> {
+        let coord = self.wrap(coord);
+        self.extend_bounds(coord);
+        self.cells.insert(coord, value)
+    }
+
+    // The bounding box keeps its size, it only ever grows.
+    pub fn remove(&mut self, coord: Coord) -> Option<T> {
+        self.cells.remove(&self.wrap(coord))
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.cells.contains_key(&self.wrap(coord))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn is_toroidal(&self) -> bool {
+        self.torus.is_some()
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Coord, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn to_dense(&self) -> Vec<Vec<T>> {
+        let Some(bounds) = self.bounds else {
+            return Vec::new();
+        };
+        (bounds.min.x..=bounds.max.x)
+            .map(|x| {
+                (bounds.min.y..=bounds.max.y)
+                    .map(|y| self.get(Coord::new(x, y)).clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn extend_bounds(&mut self, coord: Coord) {
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(coord),
+            None => self.bounds = Some(BoundingBox::new(coord, coord)),
+        }
+    }
+}
+
+impl<T: Clone> Index<Coord> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &Self::Output {
+        self.get(coord)
+    }
+}
+
+// JSON object keys have to be strings, so the cells go out as a list of
+// (coord, value) pairs, sorted to keep dumps of the same grid identical.
+#[cfg(feature = "serde")]
+mod cells {
+    use crate::coord::Coord;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        cells: &HashMap<Coord, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut pairs = cells.iter().collect::<Vec<_>>();
+        pairs.sort_by_key(|(coord, _)| **coord);
+        pairs.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Coord, T>, D::Error> {
+        Ok(Vec::<(Coord, T)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_and_defaults() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounding_box(), None);
+        grid.set(Coord::new(2, 3), '#');
+        grid.set(Coord::new(-1, 5), '#');
+        *grid.get_mut(Coord::new(0, -2)) = 'O';
+        assert_eq!(grid[Coord::new(2, 3)], '#');
+        assert_eq!(grid[Coord::new(100, 100)], '.');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(
+            grid.bounding_box(),
+            Some(BoundingBox::new(Coord::new(-1, -2), Coord::new(2, 5)))
+        );
+        grid.remove(Coord::new(2, 3));
+        assert_eq!(grid[Coord::new(2, 3)], '.');
+        assert_eq!(grid.bounding_box().unwrap().rows(), 4);
+        assert_eq!(grid.bounding_box().unwrap().cols(), 8);
+    }
+
+    #[test]
+    fn toroidal_wraps() {
+        let mut grid = SparseGrid::toroidal(0, 7, 11);
+        grid.set(Coord::new(-1, 11), 1);
+        *grid.get_mut(Coord::new(13, -22)) += 2;
+        assert_eq!(grid.wrap(Coord::new(13, -22)), Coord::new(6, 0));
+        assert_eq!(grid[Coord::new(6, 0)], 3);
+        assert_eq!(grid[Coord::new(-8, 22)], 3);
+        assert_eq!(grid[Coord::new(0, 0)], 0);
+        assert_eq!(grid.iter().count(), 1);
+        assert_eq!(
+            grid.bounding_box(),
+            Some(BoundingBox::new(Coord::new(0, 0), Coord::new(6, 10)))
+        );
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let matrix = crate::matrix::parse_matrix("..#\n#..\n.O.");
+        let grid = SparseGrid::from_dense(&matrix, '.');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid[Coord::new(2, 1)], 'O');
+        assert_eq!(grid.to_dense(), matrix);
+
+        let mut grid = SparseGrid::new(false);
+        grid.set(Coord::new(1, 1), true);
+        grid.set(Coord::new(2, 3), true);
+        assert_eq!(
+            grid.to_dense(),
+            vec![vec![true, false, false], vec![false, false, true]]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let grid = SparseGrid::from_dense(&crate::matrix::parse_matrix("..#\n#.."), '.');
+        let json = serde_json::to_string(&grid).unwrap();
+        assert!(json.starts_with(r##"{"cells":[[{"x":0,"y":2},"#"],[{"x":1,"y":0},"#"]]"##));
+        assert_eq!(
+            serde_json::from_str::<SparseGrid<char>>(&json).unwrap(),
+            grid
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::{Display, Formatter};
+
+// Minimal SVG builder for the report's visualisations. Coordinates are in user units;
+// grid helpers draw cell (row, col) as the unit square at x = col, y = row.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    elements: Vec<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn points(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Visually distinct fill colours, cycling through hues by the golden angle.
+pub fn palette(i: usize) -> String {
+    format!(
+        "hsl({:.0},65%,{}%)",
+        (i as f64 * 137.508) % 360.0,
+        [55, 70, 45][i % 3]
+    )
+}
+
+impl Svg {
+    pub fn new(width: f64, height: f64) -> Self {
+        Svg {
+            width,
+            height,
+            elements: Vec::new(),
+        }
+    }
+
+    // A canvas for a grid with the given number of rows and columns.
+    pub fn for_grid(rows: usize, cols: usize) -> Self {
+        Svg::new(cols as f64, rows as f64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) -> &mut Self {
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x, y, width, height, fill
+        ));
+        self
+    }
+
+    pub fn cell(&mut self, row: i32, col: i32, fill: &str) -> &mut Self {
+        self.rect(col as f64, row as f64, 1.0, 1.0, fill)
+    }
+
+    pub fn circle(&mut self, cx: f64, cy: f64, r: f64, fill: &str) -> &mut Self {
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            cx, cy, r, fill
+        ));
+        self
+    }
+
+    pub fn line(
+        &mut self,
+        from: (f64, f64),
+        to: (f64, f64),
+        stroke: &str,
+        width: f64,
+    ) -> &mut Self {
+        self.elements.push(format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"/>"#,
+            from.0, from.1, to.0, to.1, stroke, width
+        ));
+        self
+    }
+
+    // Closed shape; use "none" for no fill or no stroke.
+    pub fn polygon(
+        &mut self,
+        vertices: &[(f64, f64)],
+        fill: &str,
+        stroke: &str,
+        width: f64,
+    ) -> &mut Self {
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="{}" stroke="{}" stroke-width="{}"/>"#,
+            points(vertices),
+            fill,
+            stroke,
+            width
+        ));
+        self
+    }
+
+    pub fn polyline(&mut self, vertices: &[(f64, f64)], stroke: &str, width: f64) -> &mut Self {
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            points(vertices),
+            stroke,
+            width
+        ));
+        self
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, size: f64, text: &str) -> &mut Self {
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" font-size="{}" font-family="monospace">{}</text>"#,
+            x,
+            y,
+            size,
+            escape(text)
+        ));
+        self
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+            self.width, self.height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "{}", element)?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_elements() {
+        let mut svg = Svg::for_grid(2, 3);
+        assert!(svg.is_empty());
+        svg.cell(1, 2, "red")
+            .line((0.0, 0.0), (1.5, 2.0), "black", 0.1)
+            .polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)], "none", "blue", 0.2)
+            .text(0.0, 1.0, 0.5, "a<b & c");
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 3 2" shape-rendering="crispEdges">
+<rect x="2" y="1" width="1" height="1" fill="red"/>
+<line x1="0" y1="0" x2="1.5" y2="2" stroke="black" stroke-width="0.1"/>
+<polygon points="0,0 1,0 1,1" fill="none" stroke="blue" stroke-width="0.2"/>
+<text x="0" y="1" font-size="0.5" font-family="monospace">a&lt;b &amp; c</text>
+</svg>"#
+        );
+    }
+
+    #[test]
+    fn palette_colours_differ() {
+        let colours = (0..20)
+            .map(palette)
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(colours.len(), 20);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::sim::{Driver, Simulation, Stop};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue, style};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Plays a simulation in the terminal: play/pause, single steps forwards and backwards,
+// speed control and exporting the current frame. Frames larger than the terminal are
+// cropped to its top left corner.
+
+// Steps per second.
+const SPEEDS: [usize; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+// Faster speeds run several steps per redraw instead of redrawing more often.
+const MAX_FRAMES_PER_SECOND: usize = 25;
+// Snapshots kept for rewinding; some states are whole warehouses.
+const HISTORY: usize = 1000;
+
+const HELP: &str =
+    "space play/pause  \u{2192}/n step  \u{2190}/b back  +/- speed  e export  q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    PlayPause,
+    Step,
+    Back,
+    Faster,
+    Slower,
+    Export,
+    Quit,
+}
+
+fn action(key: KeyEvent) -> Option<Action> {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Char(' ') => Some(Action::PlayPause),
+        KeyCode::Right | KeyCode::Char('n') => Some(Action::Step),
+        KeyCode::Left | KeyCode::Char('b') => Some(Action::Back),
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => Some(Action::Faster),
+        KeyCode::Char('-') | KeyCode::Down => Some(Action::Slower),
+        KeyCode::Char('e') => Some(Action::Export),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+struct Player<S> {
+    name: String,
+    driver: Driver<S>,
+    playing: bool,
+    speed: usize,
+    message: String,
+    export_dir: PathBuf,
+}
+
+impl<S: Simulation + Clone> Player<S> {
+    fn new(name: &str, sim: S, export_dir: &Path) -> Self {
+        Player {
+            name: name.to_string(),
+            driver: Driver::new(sim).with_history().limit_history(HISTORY),
+            playing: false,
+            speed: 3,
+            message: String::new(),
+            export_dir: export_dir.to_path_buf(),
+        }
+    }
+
+    // Returns false to quit.
+    fn apply(&mut self, action: Action) -> bool {
+        self.message.clear();
+        match action {
+            Action::PlayPause => self.playing = !self.playing && !self.driver.sim().is_done(),
+            Action::Step => {
+                self.playing = false;
+                self.advance(1);
+            }
+            Action::Back => {
+                self.playing = false;
+                if self.driver.rewind(1) == 0 {
+                    self.message = "no history to rewind".to_string();
+                }
+            }
+            Action::Faster => self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            Action::Slower => self.speed = self.speed.saturating_sub(1),
+            Action::Export => self.message = self.export(),
+            Action::Quit => return false,
+        }
+        true
+    }
+
+    fn advance(&mut self, steps: usize) {
+        match self.driver.run(steps) {
+            Stop::Steps => {}
+            Stop::Done => {
+                self.playing = false;
+                self.message = "the simulation is done".to_string();
+            }
+            stop => unreachable!("{:?} without repeat detection or a condition", stop),
+        }
+    }
+
+    fn steps_per_frame(&self) -> usize {
+        SPEEDS[self.speed].div_ceil(MAX_FRAMES_PER_SECOND)
+    }
+
+    fn frame_interval(&self) -> Duration {
+        Duration::from_secs(1) * self.steps_per_frame() as u32 / SPEEDS[self.speed] as u32
+    }
+
+    fn tick(&mut self) {
+        if self.playing {
+            self.advance(self.steps_per_frame());
+        }
+    }
+
+    fn export(&self) -> String {
+        let path = self
+            .export_dir
+            .join(format!("{}-step{}.txt", self.name, self.driver.steps()));
+        match std::fs::write(&path, self.driver.sim().render()) {
+            Ok(()) => format!("wrote {}", path.display()),
+            Err(err) => format!("could not write {}: {}", path.display(), err),
+        }
+    }
+
+    fn status(&self) -> String {
+        let mut status = format!("{}  step {}", self.name, self.driver.steps());
+        for (name, value) in self.driver.sim().counters() {
+            status += &format!("  {} {}", name, value);
+        }
+        let state = if self.playing { "playing" } else { "paused" };
+        status + &format!("  [{} at {}/s]", state, SPEEDS[self.speed])
+    }
+}
+
+fn draw<S: Simulation + Clone>(out: &mut impl Write, player: &Player<S>) -> std::io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+    let (cols, rows) = (cols as usize, rows as usize);
+    let frame = player.driver.sim().render();
+    let lines = [player.status(), String::new()]
+        .into_iter()
+        .chain(
+            frame
+                .lines()
+                .take(rows.saturating_sub(4))
+                .map(str::to_string),
+        )
+        .chain([String::new(), format!("{}  {}", HELP, player.message)]);
+    queue!(out, cursor::MoveTo(0, 0))?;
+    for line in lines {
+        let line = line.chars().take(cols).collect::<String>();
+        queue!(
+            out,
+            style::Print(line),
+            terminal::Clear(ClearType::UntilNewLine),
+            cursor::MoveToNextLine(1)
+        )?;
+    }
+    queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+    out.flush()
+}
+
+fn run<S: Simulation + Clone>(out: &mut impl Write, player: &mut Player<S>) -> std::io::Result<()> {
+    let mut next_frame = Instant::now();
+    loop {
+        draw(out, player)?;
+        let timeout = if player.playing {
+            next_frame.saturating_duration_since(Instant::now())
+        } else {
+            Duration::from_secs(60)
+        };
+        if !event::poll(timeout)? {
+            player.tick();
+            next_frame = Instant::now() + player.frame_interval();
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            let Some(action) = action(key).filter(|_| key.kind == KeyEventKind::Press) else {
+                continue;
+            };
+            if !player.apply(action) {
+                return Ok(());
+            }
+            if action == Action::PlayPause || action == Action::Faster {
+                next_frame = Instant::now();
+            }
+        }
+    }
+}
+
+// Raw mode on the alternate screen, handed back when dropped: also on an error halfway
+// through and when a simulation panics.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter(out: &mut impl Write) -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let raw_terminal = RawTerminal;
+        execute!(out, EnterAlternateScreen, cursor::Hide)?;
+        // Unwinding only drops the guard after the panic is printed, onto the alternate
+        // screen, so leave it first.
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            RawTerminal::leave();
+            hook(info);
+        }));
+        Ok(raw_terminal)
+    }
+
+    // Nothing to do about errors here; the terminal is as good as it gets.
+    fn leave() {
+        let _ = execute!(std::io::stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        RawTerminal::leave();
+    }
+}
+
+// Takes over the terminal until the user quits. Frames are exported to the current
+// directory.
+pub fn play<S: Simulation + Clone>(name: &str, sim: S) -> std::io::Result<()> {
+    let mut player = Player::new(name, sim, Path::new(""));
+    let mut out = std::io::stdout();
+    let _raw_terminal = RawTerminal::enter(&mut out)?;
+    run(&mut out, &mut player)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::hash_of;
+
+    // A dot walking right along a row, done at the end.
+    #[derive(Clone)]
+    struct Walker {
+        at: usize,
+        width: usize,
+    }
+
+    impl Simulation for Walker {
+        fn step(&mut self) {
+            self.at += 1;
+        }
+
+        fn is_done(&self) -> bool {
+            self.at + 1 == self.width
+        }
+
+        fn state_hash(&self) -> u64 {
+            hash_of(&self.at)
+        }
+
+        fn render(&self) -> String {
+            (0..self.width)
+                .map(|i| if i == self.at { '@' } else { '.' })
+                .collect::<String>()
+                + "\n"
+        }
+
+        fn counters(&self) -> Vec<(&'static str, String)> {
+            vec![("left", (self.width - 1 - self.at).to_string())]
+        }
+    }
+
+    fn player(width: usize) -> Player<Walker> {
+        Player::new("day00", Walker { at: 0, width }, &std::env::temp_dir())
+    }
+
+    #[test]
+    fn keys() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(action(key(KeyCode::Char(' '))), Some(Action::PlayPause));
+        assert_eq!(action(key(KeyCode::Left)), Some(Action::Back));
+        assert_eq!(action(key(KeyCode::Char('c'))), None);
+        assert_eq!(
+            action(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn steps_and_rewinds() {
+        let mut player = player(5);
+        assert_eq!(player.status(), "day00  step 0  left 4  [paused at 10/s]");
+        assert!(player.apply(Action::Step));
+        assert!(player.apply(Action::Step));
+        assert_eq!(player.driver.sim().render(), "..@..\n");
+        player.apply(Action::Back);
+        assert_eq!(player.status(), "day00  step 1  left 3  [paused at 10/s]");
+        player.apply(Action::Back);
+        player.apply(Action::Back);
+        assert_eq!(player.message, "no history to rewind");
+        assert!(!player.apply(Action::Quit));
+    }
+
+    #[test]
+    fn plays_until_done() {
+        let mut player = player(400);
+        player.apply(Action::PlayPause);
+        player.tick();
+        assert_eq!(player.driver.steps(), 1);
+        for _ in 0..SPEEDS.len() {
+            player.apply(Action::Faster);
+        }
+        assert_eq!(player.steps_per_frame(), 40);
+        assert_eq!(player.frame_interval(), Duration::from_millis(40));
+        assert!(player.status().ends_with("[playing at 1000/s]"));
+        for _ in 0..10 {
+            player.tick();
+        }
+        assert_eq!(player.driver.steps(), 399);
+        assert!(!player.playing);
+        assert_eq!(player.message, "the simulation is done");
+        player.apply(Action::PlayPause);
+        assert!(!player.playing);
+
+        player.apply(Action::Slower);
+        assert_eq!(player.frame_interval(), Duration::from_millis(40));
+        for _ in 0..SPEEDS.len() {
+            player.apply(Action::Slower);
+        }
+        assert_eq!(player.frame_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn exports_frames() {
+        let mut player = player(3);
+        player.apply(Action::Step);
+        player.apply(Action::Export);
+        let path = std::env::temp_dir().join("day00-step1.txt");
+        assert_eq!(player.message, format!("wrote {}", path.display()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), ".@.\n");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+use crate::matrix::try_parse_matrix;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Problems a day's `validate` found in its input. `Runner::validate` lists them before
+// any part runs, instead of the solver panicking or looping somewhere deep inside.
+// Grid positions are written as (row, column).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Diagnostics {
+    problems: Vec<String>,
+}
+
+// Listing every stray character of a wrong file helps nobody.
+const MAX_SHOWN: usize = 20;
+
+fn positions(cells: &[(usize, usize)]) -> String {
+    cells
+        .iter()
+        .map(|(i, j)| format!("({}, {})", i, j))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// For `Diagnostics::lines`: `s` as a number, or why not.
+pub fn number<T: FromStr>(s: &str) -> Result<T, String> {
+    s.parse()
+        .map_err(|_| format!("expected a number, found {:?}", s))
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Diagnostics::default()
+    }
+
+    pub fn report(&mut self, problem: impl Into<String>) {
+        self.problems.push(problem.into());
+    }
+
+    pub fn check(&mut self, ok: bool, problem: impl FnOnce() -> String) {
+        if !ok {
+            self.problems.push(problem());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    // Parses every line of `input` as one `what`, reporting an empty input and the lines
+    // that fail as "<what> <n>: <reason>", counting from 1. Returns what parsed.
+    pub fn lines<'a, T>(
+        &mut self,
+        input: &'a str,
+        what: &str,
+        mut parse: impl FnMut(&'a str) -> Result<T, String>,
+    ) -> Vec<T> {
+        if input.is_empty() {
+            self.report(format!("expected at least one {}, found none", what));
+        }
+        let mut parsed = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            match parse(line) {
+                Ok(value) => parsed.push(value),
+                Err(reason) => self.report(format!("{} {}: {}", what, i + 1, reason)),
+            }
+        }
+        parsed
+    }
+
+    // The parts of `input` separated by blank lines, one per name, or None after
+    // reporting how many there are instead.
+    pub fn sections<'a>(&mut self, input: &'a str, names: &[&str]) -> Option<Vec<&'a str>> {
+        let sections = input.split("\n\n").collect::<Vec<_>>();
+        if sections.len() == names.len() {
+            return Some(sections);
+        }
+        self.report(format!(
+            "expected {} separated by a blank line, found {} section{}",
+            names.join(" and "),
+            sections.len(),
+            if sections.len() == 1 { "" } else { "s" }
+        ));
+        None
+    }
+
+    // A rectangular grid made of `allowed` characters. Reports stray characters, and
+    // returns the grid unless it is empty or ragged.
+    pub fn grid(&mut self, input: &str, allowed: &str) -> Option<Vec<Vec<char>>> {
+        let grid = match try_parse_matrix(input) {
+            Ok(grid) if grid.is_empty() => {
+                self.report("the grid is empty");
+                return None;
+            }
+            Ok(grid) => grid,
+            Err(err) => {
+                self.report(format!("the grid is not rectangular: {}", err));
+                return None;
+            }
+        };
+        for (i, row) in grid.iter().enumerate() {
+            for (j, c) in row.iter().enumerate() {
+                if !allowed.contains(*c) {
+                    self.report(format!("unexpected {:?} at ({}, {})", c, i, j));
+                }
+            }
+        }
+        Some(grid)
+    }
+
+    // The position of the one `c` in the grid, reporting none or several.
+    pub fn exactly_one(
+        &mut self,
+        grid: &[Vec<char>],
+        c: char,
+        what: &str,
+    ) -> Option<(usize, usize)> {
+        let cells = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell == c)
+                    .map(move |(j, _)| (i, j))
+            })
+            .collect::<Vec<_>>();
+        match cells.as_slice() {
+            [cell] => return Some(*cell),
+            [] => self.report(format!("expected one {} ({:?}), found none", what, c)),
+            _ => self.report(format!(
+                "expected one {} ({:?}), found {} at {}",
+                what,
+                c,
+                cells.len(),
+                positions(&cells)
+            )),
+        }
+        None
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for problem in self.problems.iter().take(MAX_SHOWN) {
+            writeln!(f, "  - {}", problem)?;
+        }
+        if self.problems.len() > MAX_SHOWN {
+            writeln!(f, "  ... and {} more", self.problems.len() - MAX_SHOWN)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids() {
+        let mut diagnostics = Diagnostics::new();
+        let grid = diagnostics.grid("#.^\n#x.\n^..", ".#^").unwrap();
+        assert_eq!(diagnostics.exactly_one(&grid, '^', "guard"), None);
+        assert_eq!(diagnostics.exactly_one(&grid, '#', "wall"), None);
+        assert_eq!(diagnostics.exactly_one(&grid, 'x', "x"), Some((1, 1)));
+        assert_eq!(
+            diagnostics.problems(),
+            [
+                "unexpected 'x' at (1, 1)",
+                "expected one guard ('^'), found 2 at (0, 2), (2, 0)",
+                "expected one wall ('#'), found 2 at (0, 0), (1, 0)",
+            ]
+        );
+
+        let mut diagnostics = Diagnostics::new();
+        assert_eq!(diagnostics.grid("#..\n#.", ".#"), None);
+        assert_eq!(diagnostics.grid("", ".#"), None);
+        assert_eq!(
+            diagnostics.problems(),
+            [
+                "the grid is not rectangular: row 1 has 2 columns, expected 3",
+                "the grid is empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_and_sections() {
+        let mut diagnostics = Diagnostics::new();
+        let numbers = diagnostics.lines("1\nx\n3", "number", number::<u8>);
+        assert_eq!(numbers, [1, 3]);
+        assert_eq!(
+            diagnostics.lines("", "number", number::<u8>),
+            Vec::<u8>::new()
+        );
+        let sections = diagnostics.sections("a|b\n\n1,2", &["rules", "updates"]);
+        assert_eq!(sections, Some(vec!["a|b", "1,2"]));
+        assert_eq!(diagnostics.sections("a|b", &["rules", "updates"]), None);
+        assert_eq!(
+            diagnostics.problems(),
+            [
+                "number 2: expected a number, found \"x\"",
+                "expected at least one number, found none",
+                "expected rules and updates separated by a blank line, found 1 section",
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_problems() {
+        let mut diagnostics = Diagnostics::new();
+        assert!(diagnostics.is_empty());
+        diagnostics.check(true, || unreachable!());
+        diagnostics.check(false, || "track branches at (12, 40)".to_string());
+        assert_eq!(diagnostics.to_string(), "  - track branches at (12, 40)\n");
+        for i in 0..MAX_SHOWN {
+            diagnostics.report(format!("problem {}", i));
+        }
+        assert!(diagnostics
+            .to_string()
+            .ends_with("  - problem 18\n  ... and 1 more\n"));
+    }
+}